@@ -24,8 +24,13 @@
 //!     .expect("Failed to initialize logging");
 //! ```
 
-use crate::init_logging;
-use crate::{FileLogConfig, LogConfig, Result, RotationTrigger};
+use crate::hooks::HookList;
+use crate::tracing_init::init_logging_with_hooks;
+use crate::{
+    Compression, Durability, FileHeader, FileLogConfig, FileNaming, LogConfig, LoggingHandle,
+    OversizedRecord, Result, Retention, RotationEvent, RotationStrategy, RotationTrigger, Timezone,
+    WriteFailurePolicy,
+};
 use std::path::PathBuf;
 
 /// A builder for configuring and initializing logging.
@@ -35,6 +40,7 @@ use std::path::PathBuf;
 #[derive(Debug, Clone)]
 pub struct LogBuilder {
     config: LogConfig,
+    hooks: HookList,
 }
 
 impl LogBuilder {
@@ -42,12 +48,16 @@ impl LogBuilder {
     pub fn new() -> Self {
         Self {
             config: LogConfig::new(),
+            hooks: HookList::default(),
         }
     }
 
     /// Create a LogBuilder from an existing configuration.
     pub fn from_config(config: LogConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            hooks: HookList::default(),
+        }
     }
 
     /// Enable or disable console logging.
@@ -97,6 +107,156 @@ impl LogBuilder {
         self
     }
 
+    /// Set the retention policy for time-suffixed log files.
+    ///
+    /// If no file is configured, this will create a default file at "app.log".
+    pub fn with_retention(mut self, retention: Retention) -> Self {
+        self.file_config_mut().retention = retention;
+        self
+    }
+
+    /// Set the compression for rotated log files.
+    ///
+    /// If no file is configured, this will create a default file at "app.log".
+    pub fn with_compression(mut self, compress: Compression) -> Self {
+        self.file_config_mut().compress = compress;
+        self
+    }
+
+    /// Set how the active file is moved aside during size-based rotation.
+    ///
+    /// If no file is configured, this will create a default file at "app.log".
+    pub fn with_rotation_strategy(mut self, strategy: RotationStrategy) -> Self {
+        self.file_config_mut().strategy = strategy;
+        self
+    }
+
+    /// Set the maximum total size in bytes of the active log file and all rotated files.
+    ///
+    /// If no file is configured, this will create a default file at "app.log".
+    pub fn with_max_total_size(mut self, max_total_size: u64) -> Self {
+        self.file_config_mut().max_total_size = Some(max_total_size);
+        self
+    }
+
+    /// Set the naming template for active and rotated log files.
+    ///
+    /// If no file is configured, this will create a default file at "app.log".
+    pub fn with_naming(mut self, naming: FileNaming) -> Self {
+        self.file_config_mut().naming = naming;
+        self
+    }
+
+    /// Keep a symlink pointing at the active log file, re-pointed on every rotation (Unix only).
+    ///
+    /// With time-based rotation, passing the configured file name (e.g. "app.log")
+    /// lets `tail -F app.log` follow the active file across periods.
+    ///
+    /// If no file is configured, this will create a default file at "app.log".
+    pub fn with_symlink(mut self, symlink: impl Into<PathBuf>) -> Self {
+        self.file_config_mut().symlink = Some(symlink.into());
+        self
+    }
+
+    /// Move rotated files to a separate directory, keeping only the active file
+    /// in the log directory. A relative path is resolved against the log directory.
+    ///
+    /// If no file is configured, this will create a default file at "app.log".
+    pub fn with_archive_dir(mut self, archive_dir: impl Into<PathBuf>) -> Self {
+        self.file_config_mut().archive_dir = Some(archive_dir.into());
+        self
+    }
+
+    /// Write a header at the start of every new log file, e.g. a comment line
+    /// or a JSON record describing the app, host and previous file.
+    ///
+    /// If no file is configured, this will create a default file at "app.log".
+    pub fn with_header(mut self, header: FileHeader) -> Self {
+        self.file_config_mut().header = Some(header);
+        self
+    }
+
+    /// Set what to do with records that can't be written to the log file.
+    ///
+    /// If no file is configured, this will create a default file at "app.log".
+    pub fn with_write_failure(mut self, write_failure: WriteFailurePolicy) -> Self {
+        self.file_config_mut().write_failure = write_failure;
+        self
+    }
+
+    /// Set how far records are pushed towards the disk when the file is flushed.
+    ///
+    /// If no file is configured, this will create a default file at "app.log".
+    pub fn with_durability(mut self, durability: Durability) -> Self {
+        self.file_config_mut().durability = durability;
+        self
+    }
+
+    /// Set the size of the write buffer in bytes, 0 to disable buffering.
+    ///
+    /// If no file is configured, this will create a default file at "app.log".
+    pub fn with_buffer_size(mut self, buffer_size: u64) -> Self {
+        self.file_config_mut().buffer_size = buffer_size;
+        self
+    }
+
+    /// Enable or disable coordination with other processes writing to the same files.
+    ///
+    /// If no file is configured, this will create a default file at "app.log".
+    pub fn with_multi_process(mut self, multi_process: bool) -> Self {
+        self.file_config_mut().multi_process = multi_process;
+        self
+    }
+
+    /// Set what to do with records larger than the maximum file size.
+    ///
+    /// If no file is configured, this will create a default file at "app.log".
+    pub fn with_oversized_records(mut self, oversized_records: OversizedRecord) -> Self {
+        self.file_config_mut().oversized_records = oversized_records;
+        self
+    }
+
+    /// Enable or disable rotating a non-empty log file when logging starts.
+    ///
+    /// If no file is configured, this will create a default file at "app.log".
+    pub fn with_rotate_on_start(mut self, rotate_on_start: bool) -> Self {
+        self.file_config_mut().rotate_on_start = rotate_on_start;
+        self
+    }
+
+    /// Set the Unix mode of log files and rotated archives, e.g. `0o640`.
+    ///
+    /// If no file is configured, this will create a default file at "app.log".
+    pub fn with_file_mode(mut self, file_mode: u32) -> Self {
+        self.file_config_mut().file_mode = Some(file_mode);
+        self
+    }
+
+    /// Set the Unix mode of directories created for the log files, e.g. `0o750`.
+    ///
+    /// If no file is configured, this will create a default file at "app.log".
+    pub fn with_dir_mode(mut self, dir_mode: u32) -> Self {
+        self.file_config_mut().dir_mode = Some(dir_mode);
+        self
+    }
+
+    /// Set the user and group ids owning log files and created directories.
+    ///
+    /// If no file is configured, this will create a default file at "app.log".
+    pub fn with_owner(mut self, owner: Option<u32>, group: Option<u32>) -> Self {
+        let file_config = self.file_config_mut();
+        file_config.owner = owner;
+        file_config.group = group;
+        self
+    }
+
+    /// Get the file configuration, creating a default one at "app.log" if missing.
+    fn file_config_mut(&mut self) -> &mut FileLogConfig {
+        self.config
+            .file
+            .get_or_insert_with(|| FileLogConfig::new("app.log"))
+    }
+
     /// Show target/module in logs
     pub fn with_target(mut self, target: bool) -> Self {
         self.config = self.config.with_target(target);
@@ -115,6 +275,29 @@ impl LogBuilder {
         self
     }
 
+    /// Set the time zone for rotation boundaries and timestamps.
+    ///
+    /// `Local` is resolved when logging is initialized, and again whenever
+    /// the file writer starts a new file; timestamps and rotation always
+    /// use the same offset.
+    pub fn with_timezone(mut self, timezone: Timezone) -> Self {
+        self.config = self.config.with_timezone(timezone);
+        self
+    }
+
+    /// Register a hook to run after every rotation of the log file.
+    ///
+    /// Hooks receive the closed and new file paths, the reason and the size of
+    /// the closed file. They run in order on a background thread, so a slow
+    /// hook never stalls logging.
+    pub fn on_rotate<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&RotationEvent) + Send + Sync + 'static,
+    {
+        self.hooks.0.push(std::sync::Arc::new(hook));
+        self
+    }
+
     /// Get the current configuration without initializing.
     pub fn build(self) -> LogConfig {
         self.config
@@ -131,7 +314,18 @@ impl LogBuilder {
     /// - File operations fail
     /// - Invalid configuration is provided
     pub fn init(self) -> Result<()> {
-        init_logging(&self.config)
+        self.init_with_handle().map(|_| ())
+    }
+
+    /// Initialize logging and return a handle to control it afterwards.
+    ///
+    /// The handle can reopen the log file, e.g. after logrotate moved it.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`init`](Self::init).
+    pub fn init_with_handle(self) -> Result<LoggingHandle> {
+        init_logging_with_hooks(&self.config, &self.hooks)
     }
 }
 
@@ -223,6 +417,144 @@ mod tests {
         assert_eq!(file_config.rotation, RotationTrigger::size(1024 * 1024, 5));
     }
 
+    #[test]
+    fn test_builder_with_retention() {
+        let builder = LogBuilder::new()
+            .with_file("test.log")
+            .with_retention(Retention::periods(7));
+
+        let file_config = builder.build().file.unwrap();
+        assert_eq!(file_config.path, PathBuf::from("test.log"));
+        assert_eq!(file_config.retention, Retention::periods(7));
+    }
+
+    #[test]
+    fn test_builder_with_compression() {
+        let builder = LogBuilder::new().with_compression(Compression::Gzip);
+
+        let file_config = builder.build().file.unwrap();
+        assert_eq!(file_config.path, PathBuf::from("app.log"));
+        assert_eq!(file_config.compress, Compression::Gzip);
+    }
+
+    #[test]
+    fn test_builder_with_rotation_strategy() {
+        let builder = LogBuilder::new()
+            .with_file("test.log")
+            .with_rotation_strategy(RotationStrategy::Rename);
+
+        let file_config = builder.build().file.unwrap();
+        assert_eq!(file_config.strategy, RotationStrategy::Rename);
+    }
+
+    #[test]
+    fn test_builder_with_max_total_size() {
+        let builder = LogBuilder::new()
+            .with_file("test.log")
+            .with_max_total_size(2 * 1024 * 1024 * 1024);
+
+        let file_config = builder.build().file.unwrap();
+        assert_eq!(file_config.max_total_size, Some(2 * 1024 * 1024 * 1024));
+    }
+
+    #[test]
+    fn test_builder_with_naming() {
+        let naming = FileNaming::new("{stem}-{date}.{index}.{ext}").unwrap();
+        let builder = LogBuilder::new()
+            .with_file("test.log")
+            .with_naming(naming.clone());
+
+        let file_config = builder.build().file.unwrap();
+        assert_eq!(file_config.naming, naming);
+    }
+
+    #[test]
+    fn test_builder_with_write_failure() {
+        let builder = LogBuilder::new().with_write_failure(WriteFailurePolicy::Retry);
+
+        let file_config = builder.build().file.unwrap();
+        assert_eq!(file_config.write_failure, WriteFailurePolicy::Retry);
+    }
+
+    #[test]
+    fn test_builder_with_durability() {
+        let builder = LogBuilder::new()
+            .with_durability(Durability::FsyncOnFlush)
+            .with_buffer_size(0);
+
+        let file_config = builder.build().file.unwrap();
+        assert_eq!(file_config.durability, Durability::FsyncOnFlush);
+        assert_eq!(file_config.buffer_size, 0);
+    }
+
+    #[test]
+    fn test_builder_with_multi_process() {
+        let builder = LogBuilder::new().with_multi_process(true);
+        assert!(builder.build().file.unwrap().multi_process);
+    }
+
+    #[test]
+    fn test_builder_with_oversized_records() {
+        let builder = LogBuilder::new().with_oversized_records(OversizedRecord::Truncate);
+
+        let file_config = builder.build().file.unwrap();
+        assert_eq!(file_config.oversized_records, OversizedRecord::Truncate);
+    }
+
+    #[test]
+    fn test_builder_with_rotate_on_start() {
+        let builder = LogBuilder::new().with_rotate_on_start(true);
+        assert!(builder.build().file.unwrap().rotate_on_start);
+    }
+
+    #[test]
+    fn test_builder_with_permissions() {
+        let builder = LogBuilder::new()
+            .with_file_mode(0o640)
+            .with_dir_mode(0o750)
+            .with_owner(None, Some(4));
+
+        let file_config = builder.build().file.unwrap();
+        assert_eq!(file_config.file_mode, Some(0o640));
+        assert_eq!(file_config.dir_mode, Some(0o750));
+        assert_eq!(file_config.owner, None);
+        assert_eq!(file_config.group, Some(4));
+    }
+
+    #[test]
+    fn test_builder_with_symlink() {
+        let builder = LogBuilder::new()
+            .with_file("logs/test.log")
+            .with_symlink("test.log");
+
+        let file_config = builder.build().file.unwrap();
+        assert_eq!(file_config.symlink, Some(PathBuf::from("test.log")));
+    }
+
+    #[test]
+    fn test_builder_with_archive_dir() {
+        let builder = LogBuilder::new()
+            .with_file("logs/test.log")
+            .with_archive_dir("archive");
+
+        let file_config = builder.build().file.unwrap();
+        assert_eq!(file_config.archive_dir, Some(PathBuf::from("archive")));
+    }
+
+    #[test]
+    fn test_builder_with_header() {
+        let builder = LogBuilder::new().with_header(FileHeader::text("# {app}"));
+
+        let file_config = builder.build().file.unwrap();
+        assert_eq!(file_config.header, Some(FileHeader::text("# {app}")));
+    }
+
+    #[test]
+    fn test_builder_on_rotate() {
+        let builder = LogBuilder::new().on_rotate(|_| {}).on_rotate(|_| {});
+        assert_eq!(builder.hooks.0.len(), 2);
+    }
+
     #[test]
     fn test_builder_with_target() {
         let builder = LogBuilder::new().with_target(true);
@@ -243,4 +575,11 @@ mod tests {
         let config = builder.build();
         assert!(config.thread_names);
     }
+
+    #[test]
+    fn test_builder_with_timezone() {
+        let builder = LogBuilder::new().with_timezone(Timezone::Fixed(-5 * 3600));
+        let config = builder.build();
+        assert_eq!(config.timezone, Timezone::Fixed(-5 * 3600));
+    }
 }
@@ -0,0 +1,139 @@
+use std::time::SystemTime;
+
+/// A source of wall clock time for rotation.
+///
+/// [`RotatingWriter`](crate::RotatingWriter) reads the time from a clock to
+/// name files and decide when a period ends, and the local offset from UTC
+/// for [`Timezone::Local`](crate::Timezone::Local). Use [`SystemClock`] in
+/// production; with the `testing` feature, `ManualClock` lets tests move
+/// time to midnight, month ends or across DST changes without waiting.
+pub trait Clock: Send + Sync {
+    /// The current time.
+    fn now(&self) -> SystemTime;
+
+    /// The local offset from UTC at `at`, in seconds east of UTC, or `None`
+    /// if it can't be determined.
+    ///
+    /// The default looks it up in the system's time zone database, which can
+    /// fail, notably in multi-threaded processes on Unix.
+    fn local_offset_at(&self, at: SystemTime) -> Option<i32> {
+        #[cfg(feature = "time")]
+        {
+            time::UtcOffset::local_offset_at(at.into())
+                .ok()
+                .map(time::UtcOffset::whole_seconds)
+        }
+        #[cfg(not(feature = "time"))]
+        {
+            let _ = at;
+            None
+        }
+    }
+}
+
+/// The system's wall clock.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+#[cfg(any(test, feature = "testing"))]
+pub use manual::ManualClock;
+
+#[cfg(any(test, feature = "testing"))]
+mod manual {
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, SystemTime};
+
+    use super::Clock;
+
+    /// A clock that only moves when told to, for tests.
+    ///
+    /// Local time is UTC unless changed with
+    /// [`set_local_offset`](Self::set_local_offset). Clones share the same
+    /// time and offsets, so a test can keep one clone and hand another to the
+    /// writer.
+    #[derive(Debug, Clone)]
+    pub struct ManualClock {
+        now: Arc<Mutex<SystemTime>>,
+        /// Local offset changes as (from, seconds east of UTC), in order.
+        offsets: Arc<Mutex<Vec<(SystemTime, i32)>>>,
+    }
+
+    impl ManualClock {
+        /// Create a clock stopped at `now`.
+        pub fn new(now: SystemTime) -> Self {
+            Self {
+                now: Arc::new(Mutex::new(now)),
+                offsets: Arc::new(Mutex::new(Vec::new())),
+            }
+        }
+
+        /// Create a clock stopped at `secs` seconds after the Unix epoch.
+        pub fn from_unix_timestamp(secs: u64) -> Self {
+            Self::new(SystemTime::UNIX_EPOCH + Duration::from_secs(secs))
+        }
+
+        /// Set the time.
+        pub fn set(&self, now: SystemTime) {
+            *self.now.lock().unwrap() = now;
+        }
+
+        /// Move the time forward by `duration`.
+        pub fn advance(&self, duration: Duration) {
+            *self.now.lock().unwrap() += duration;
+        }
+
+        /// Use `seconds` east of UTC as the local offset from `from` on, e.g.
+        /// to simulate a DST change.
+        pub fn set_local_offset(&self, from: SystemTime, seconds: i32) {
+            let mut offsets = self.offsets.lock().unwrap();
+            offsets.retain(|&(start, _)| start < from);
+            offsets.push((from, seconds));
+        }
+    }
+
+    impl Clock for ManualClock {
+        fn now(&self) -> SystemTime {
+            *self.now.lock().unwrap()
+        }
+
+        fn local_offset_at(&self, at: SystemTime) -> Option<i32> {
+            let offsets = self.offsets.lock().unwrap();
+            let offset = offsets.iter().rev().find(|&&(from, _)| from <= at);
+            Some(offset.map_or(0, |&(_, seconds)| seconds))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_manual_clock() {
+        let clock = ManualClock::from_unix_timestamp(1_000);
+        let shared = clock.clone();
+
+        shared.advance(Duration::from_secs(60));
+        assert_eq!(
+            clock.now(),
+            SystemTime::UNIX_EPOCH + Duration::from_secs(1_060)
+        );
+
+        shared.set(SystemTime::UNIX_EPOCH);
+        assert_eq!(clock.now(), SystemTime::UNIX_EPOCH);
+
+        let at = |secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs);
+        shared.set_local_offset(at(100), 3600);
+        shared.set_local_offset(at(200), 7200);
+        assert_eq!(clock.local_offset_at(at(99)), Some(0));
+        assert_eq!(clock.local_offset_at(at(100)), Some(3600));
+        assert_eq!(clock.local_offset_at(at(250)), Some(7200));
+    }
+}
@@ -0,0 +1,336 @@
+use std::collections::HashMap;
+use std::fs::{File, Metadata};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+
+use crate::Compression;
+use crate::hooks::{RotationEvent, RotationHooks};
+use crate::permissions::Permissions;
+use crate::writer::{ProcessLock, same_file};
+
+/// A file waiting for compression.
+#[derive(Debug)]
+struct Job {
+    /// Current path of the file.
+    path: PathBuf,
+    /// Rotation event to dispatch once the file is compressed.
+    event: Option<RotationEvent>,
+}
+
+/// Files waiting for compression, keyed by job id.
+#[derive(Debug, Default)]
+struct Pending {
+    next_id: u64,
+    files: HashMap<u64, Job>,
+}
+
+/// Compresses rotated files on a background thread.
+///
+/// Files waiting for compression may be shifted or deleted by the writer while
+/// the worker is busy. All such changes go through [`Compressor::rename`] and
+/// [`Compressor::remove`], which keep the pending list up to date, so the worker
+/// always replaces a file under its current name.
+///
+/// In multi-process mode, other processes shift and delete files without
+/// telling the worker. The worker then swaps in the compressed file with the
+/// lock shared by the processes held, wherever the file it compressed is at
+/// that point, and compresses into temporary files locked against the other
+/// processes' workers.
+#[derive(Debug)]
+pub(crate) struct Compressor {
+    pending: Arc<Mutex<Pending>>,
+    sender: Option<Sender<u64>>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl Compressor {
+    /// Start a compressor with its background worker.
+    ///
+    /// Compressed files get `permissions`. Rotation events submitted with files
+    /// are dispatched to `hooks` once the file has been compressed. In
+    /// multi-process mode, `lock_path` is the lock file shared by the processes.
+    /// `on_compressed` runs on the worker after each file it's done with.
+    pub(crate) fn new(
+        compression: Compression,
+        permissions: Permissions,
+        hooks: Arc<RotationHooks>,
+        lock_path: Option<&Path>,
+        on_compressed: Box<dyn Fn() + Send>,
+    ) -> io::Result<Self> {
+        if !compression.is_supported() {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!("{:?} compression is not enabled in this build", compression),
+            ));
+        }
+
+        // A handle of its own, as the lock is held per handle and the writer's
+        // wouldn't keep it from this thread
+        let lock_file = lock_path.map(File::open).transpose()?;
+
+        let pending = Arc::new(Mutex::new(Pending::default()));
+        let (sender, receiver) = mpsc::channel::<u64>();
+
+        let worker_pending = Arc::clone(&pending);
+        let worker = std::thread::Builder::new()
+            .name("lazylog-compress".to_string())
+            .spawn(move || {
+                for id in receiver {
+                    let (result, event) = compress_pending(
+                        &worker_pending,
+                        id,
+                        compression,
+                        permissions,
+                        lock_file.as_ref(),
+                    );
+                    if let Err(e) = result {
+                        eprintln!("lazylog: failed to compress rotated log file: {}", e);
+                    }
+                    if let Some(event) = event {
+                        hooks.dispatch(event);
+                    }
+                    on_compressed();
+                }
+            })?;
+
+        Ok(Self {
+            pending,
+            sender: Some(sender),
+            worker: Some(worker),
+        })
+    }
+
+    /// Queue a rotated file for compression, with the event of the rotation
+    /// that produced it, if any.
+    pub(crate) fn submit(&self, path: PathBuf, event: Option<RotationEvent>) {
+        let id = {
+            let mut pending = self.pending.lock().unwrap();
+            let id = pending.next_id;
+            pending.next_id += 1;
+            pending.files.insert(id, Job { path, event });
+            id
+        };
+
+        if let Some(sender) = &self.sender {
+            // The worker only exits once the sender is dropped
+            let _ = sender.send(id);
+        }
+    }
+
+    /// Rename a rotated file, keeping track of it if it's waiting for compression.
+    pub(crate) fn rename(&self, from: &Path, to: &Path) -> io::Result<()> {
+        let mut pending = self.pending.lock().unwrap();
+        std::fs::rename(from, to)?;
+        for job in pending.files.values_mut() {
+            if job.path == from {
+                job.path = to.to_path_buf();
+            }
+        }
+        Ok(())
+    }
+
+    /// Check if a rotated file is waiting for compression.
+    pub(crate) fn is_pending(&self, path: &Path) -> bool {
+        let pending = self.pending.lock().unwrap();
+        pending.files.values().any(|job| job.path == path)
+    }
+
+    /// Delete a rotated file, cancelling its compression if it's pending.
+    pub(crate) fn remove(&self, path: &Path) -> io::Result<()> {
+        let mut pending = self.pending.lock().unwrap();
+        pending.files.retain(|_, job| job.path != path);
+        std::fs::remove_file(path)
+    }
+}
+
+impl Drop for Compressor {
+    fn drop(&mut self) {
+        // Closing the channel lets the worker finish the queue and exit. The
+        // worker may drop the last handle to the writer itself, and can't
+        // wait for itself
+        self.sender.take();
+        if let Some(worker) = self.worker.take()
+            && worker.thread().id() != std::thread::current().id()
+        {
+            let _ = worker.join();
+        }
+    }
+}
+
+/// Compress a pending file and replace it with its compressed version.
+///
+/// Returns the job's rotation event, pointing at the file under its final
+/// name, unless the file was deleted in the meantime.
+fn compress_pending(
+    pending: &Mutex<Pending>,
+    id: u64,
+    compression: Compression,
+    permissions: Permissions,
+    lock_file: Option<&File>,
+) -> (io::Result<()>, Option<RotationEvent>) {
+    // Hand the event over with the path the file ended up at
+    let finish = |job: Job, path: PathBuf| {
+        job.event.map(|event| RotationEvent {
+            closed: path,
+            ..event
+        })
+    };
+    // Keep the file uncompressed, under its current name
+    let keep = |result: io::Result<()>| {
+        let event = pending.lock().unwrap().files.remove(&id).and_then(|job| {
+            let path = job.path.clone();
+            finish(job, path)
+        });
+        (result, event)
+    };
+
+    // Open under the lock so the file can't be shifted in between; the open
+    // handle stays valid if the file is renamed afterwards, and keeps its
+    // identity from being reused
+    let (source_path, extension, source, identity) = {
+        let mut pending = pending.lock().unwrap();
+        let Some(source_path) = pending.files.get(&id).map(|job| job.path.clone()) else {
+            // Deleted before we got to it
+            return (Ok(()), None);
+        };
+        let Some(extension) = compression.extension() else {
+            let job = pending.files.remove(&id).unwrap();
+            return (Ok(()), finish(job, source_path));
+        };
+        let opened = File::open(&source_path).and_then(|source| {
+            let identity = source.metadata()?;
+            Ok((source, identity))
+        });
+        match opened {
+            Ok((source, identity)) => (source_path, extension, source, identity),
+            Err(e) => {
+                pending.files.remove(&id);
+                return if e.kind() == io::ErrorKind::NotFound {
+                    (Ok(()), None)
+                } else {
+                    (Err(e), None)
+                };
+            }
+        }
+    };
+
+    // Hidden temporary name that never matches rotated file names
+    let file_name = source_path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let temp_path = source_path.with_file_name(format!(".{}.{}.tmp", file_name, extension));
+    let temp = match open_temp(&temp_path, permissions) {
+        Ok(Some(temp)) => temp,
+        // Another process is compressing the same file
+        Ok(None) => return keep(Ok(())),
+        Err(e) => return keep(Err(e)),
+    };
+
+    let swap_lock = compress_file(&source, &temp, compression)
+        .and_then(|()| lock_file.map(ProcessLock::acquire).transpose());
+    let _swap_lock = match swap_lock {
+        Ok(lock) => lock,
+        Err(e) => {
+            let _ = std::fs::remove_file(&temp_path);
+            return keep(Err(e));
+        }
+    };
+
+    // Swap in the compressed file where the source is now
+    let mut pending = pending.lock().unwrap();
+    let job = pending.files.remove(&id);
+    match job.and_then(|job| Some((locate(&job.path, &identity)?, job))) {
+        Some((path, job)) => {
+            let compressed = PathBuf::from(format!("{}.{}", path.display(), extension));
+            let result =
+                std::fs::rename(&temp_path, &compressed).and_then(|()| std::fs::remove_file(&path));
+            match result {
+                Ok(()) => (Ok(()), finish(job, compressed)),
+                Err(e) => (Err(e), finish(job, path)),
+            }
+        }
+        // Deleted in the meantime
+        None => (std::fs::remove_file(&temp_path), None),
+    }
+}
+
+/// Open the temporary file at `path` for compressing into, locked so that
+/// other processes leave it alone until it's renamed into place.
+///
+/// Returns none if another process holds it.
+fn open_temp(path: &Path, permissions: Permissions) -> io::Result<Option<File>> {
+    let temp = permissions
+        .open_options()
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)?;
+    match temp.try_lock() {
+        Ok(()) => {}
+        Err(std::fs::TryLockError::WouldBlock) => return Ok(None),
+        Err(std::fs::TryLockError::Error(e)) => return Err(e),
+    }
+    // The holder may have renamed it into place before releasing it
+    let opened = temp.metadata()?;
+    if !std::fs::metadata(path).is_ok_and(|on_disk| same_file(&on_disk, &opened)) {
+        return Ok(None);
+    }
+    temp.set_len(0)?;
+    permissions.apply_to_file(&temp)?;
+    Ok(Some(temp))
+}
+
+/// Find the file with the identity of `source`: at `path`, or in the same
+/// directory under another name if another process shifted it.
+fn locate(path: &Path, source: &Metadata) -> Option<PathBuf> {
+    if std::fs::metadata(path).is_ok_and(|metadata| same_file(&metadata, source)) {
+        return Some(path.to_path_buf());
+    }
+    if cfg!(not(unix)) {
+        // Without file identities, a missing file is taken as deleted
+        return None;
+    }
+    let dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    std::fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .find(|entry| {
+            std::fs::symlink_metadata(entry.path())
+                .is_ok_and(|metadata| same_file(&metadata, source))
+        })
+        .map(|entry| entry.path())
+}
+
+/// Compress `source` into the empty file `dest`.
+#[cfg_attr(not(any(feature = "gzip", feature = "zstd")), allow(unused_variables))]
+fn compress_file(source: &File, dest: &File, compression: Compression) -> io::Result<()> {
+    match compression {
+        Compression::None => Ok(()),
+        #[cfg(feature = "gzip")]
+        Compression::Gzip => {
+            let mut source = source;
+            let mut encoder = flate2::write::GzEncoder::new(dest, flate2::Compression::default());
+            io::copy(&mut source, &mut encoder)?;
+            encoder.finish()?.sync_all()
+        }
+        #[cfg(feature = "zstd")]
+        Compression::Zstd => {
+            let mut source = source;
+            let mut encoder = zstd::stream::Encoder::new(dest, 0)?;
+            io::copy(&mut source, &mut encoder)?;
+            encoder.finish()?.sync_all()
+        }
+        #[allow(unreachable_patterns)]
+        _ => Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!("{:?} compression is not enabled in this build", compression),
+        )),
+    }
+}
@@ -1,7 +1,11 @@
 use serde::{Deserialize, Serialize};
 
 /// Configuration for logging
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// Like [`FileLogConfig`], it can't be built with a literal outside this
+/// crate: start from [`LogConfig::new`] and use the `with_*` methods.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
 pub struct LogConfig {
     /// Enable console logging
     #[serde(default)]
@@ -23,6 +27,9 @@ pub struct LogConfig {
     /// Show thread names in logs
     #[serde(default)]
     pub thread_names: bool,
+    /// Time zone for rotation boundaries and timestamps
+    #[serde(default)]
+    pub timezone: crate::Timezone,
 }
 
 impl LogConfig {
@@ -36,6 +43,7 @@ impl LogConfig {
             target: false,
             thread_ids: false,
             thread_names: false,
+            timezone: crate::Timezone::Local,
         }
     }
 
@@ -80,6 +88,12 @@ impl LogConfig {
         self.thread_names = thread_names;
         self
     }
+
+    /// Set the time zone for rotation boundaries and timestamps
+    pub fn with_timezone(mut self, timezone: crate::Timezone) -> Self {
+        self.timezone = timezone;
+        self
+    }
 }
 
 impl Default for LogConfig {
@@ -96,14 +110,158 @@ fn default_format() -> String {
     "text".to_string()
 }
 
+fn default_buffer_size() -> u64 {
+    64 * 1024
+}
+
+fn default_reopen_check() -> std::time::Duration {
+    std::time::Duration::from_secs(1)
+}
+
 /// Configuration for file logging
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// New settings are added over time, so the struct can't be built with a
+/// literal outside this crate: start from [`FileLogConfig::new`] and use the
+/// `with_*` methods, or deserialize it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(remote = "Self")]
+#[non_exhaustive]
 pub struct FileLogConfig {
     /// Path to the log file
     pub path: std::path::PathBuf,
+    /// What sizes without a unit mean: `kilobytes` (default) or `bytes`
+    #[serde(default)]
+    pub plain_sizes: crate::PlainSizeUnit,
     /// Log rotation trigger
     #[serde(default)]
     pub rotation: crate::RotationTrigger,
+    /// Retention policy for time-suffixed files
+    #[serde(default)]
+    pub retention: crate::Retention,
+    /// Compression for rotated files
+    #[serde(default)]
+    pub compress: crate::Compression,
+    /// How the active file is moved aside during size-based rotation
+    #[serde(default)]
+    pub strategy: crate::RotationStrategy,
+    /// Maximum total size in bytes of the active file and all rotated files.
+    /// Can be specified as a string with a binary unit (B, K/KB/KiB, M/MB/MiB,
+    /// G/GB/GiB, T/TB/TiB, case-insensitive; KB and MB mean 1024 and 1024² bytes),
+    /// possibly decimal like "1.5G", or as an integer in KB unless `plain_sizes` is `bytes`.
+    #[serde(
+        default,
+        with = "crate::rotation::opt_size",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub max_total_size: Option<u64>,
+    /// Naming template for active and rotated files
+    #[serde(default)]
+    pub naming: crate::FileNaming,
+    /// Symlink kept pointing at the active file (Unix only).
+    /// A relative path is resolved against the directory of `path`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symlink: Option<std::path::PathBuf>,
+    /// Directory rotated files are moved to, keeping only the active file in
+    /// the directory of `path`. A relative path is resolved against the
+    /// directory of `path`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archive_dir: Option<std::path::PathBuf>,
+    /// Header written at the start of every new file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub header: Option<crate::FileHeader>,
+    /// How often to check whether the active file was moved or deleted by
+    /// another process, in which case it is reopened. Zero checks on every write.
+    #[serde(default = "default_reopen_check", with = "crate::rotation::duration")]
+    pub reopen_check: std::time::Duration,
+    /// What to do with records that can't be written, e.g. when the disk is full
+    #[serde(default)]
+    pub write_failure: crate::WriteFailurePolicy,
+    /// How far records are pushed towards the disk when the file is flushed
+    #[serde(default)]
+    pub durability: crate::Durability,
+    /// Size of the write buffer in bytes, 0 to write records straight to the file.
+    /// Can be specified as a string with a binary unit (B, K/KB/KiB, M/MB/MiB,
+    /// G/GB/GiB, T/TB/TiB, case-insensitive; KB and MB mean 1024 and 1024² bytes),
+    /// possibly decimal like "1.5G", or as an integer in KB unless `plain_sizes` is `bytes`.
+    #[serde(default = "default_buffer_size", with = "crate::rotation::size")]
+    pub buffer_size: u64,
+    /// Coordinate rotation with other processes writing to the same files,
+    /// using an advisory lock on a `.<name>.lock` file next to them. Records
+    /// are written unbuffered in this mode.
+    #[serde(default)]
+    pub multi_process: bool,
+    /// What to do with records larger than the maximum file size
+    #[serde(default)]
+    pub oversized_records: crate::OversizedRecord,
+    /// Rotate a non-empty active file when logging starts
+    #[serde(default)]
+    pub rotate_on_start: bool,
+    /// Unix mode of log files and rotated archives, e.g. "0640"
+    #[serde(
+        default,
+        with = "crate::permissions::opt_mode",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub file_mode: Option<u32>,
+    /// Unix mode of directories created for the log files, e.g. "0750"
+    #[serde(
+        default,
+        with = "crate::permissions::opt_mode",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub dir_mode: Option<u32>,
+    /// User id owning log files and created directories (Unix only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<u32>,
+    /// Group id owning log files and created directories (Unix only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<u32>,
+}
+
+impl Serialize for FileLogConfig {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        FileLogConfig::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for FileLogConfig {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::Error;
+
+        // Sizes without a unit depend on `plain_sizes`, which may come after
+        // them: give them a unit before reading the rest
+        let mut value = serde_json::Value::deserialize(deserializer)?;
+        let plain_sizes = match value.get("plain_sizes") {
+            Some(unit) => crate::PlainSizeUnit::deserialize(unit).map_err(D::Error::custom)?,
+            None => crate::PlainSizeUnit::default(),
+        };
+        for field in ["max_total_size", "buffer_size"] {
+            if let Some(size) = value.get_mut(field) {
+                crate::rotation::normalize_size(size, plain_sizes).map_err(D::Error::custom)?;
+            }
+        }
+
+        // The backups of a time-based trigger are the periods it retains;
+        // the trigger itself is left without them
+        let mut max_periods = None;
+        if let Some(rotation) = value.get_mut("rotation") {
+            let (trigger, backups) = crate::rotation::parse_rotation(rotation.take(), plain_sizes)
+                .map_err(D::Error::custom)?;
+            *rotation = serde_json::to_value(&trigger).map_err(D::Error::custom)?;
+            max_periods = backups;
+        }
+        let mut config = FileLogConfig::deserialize(value).map_err(D::Error::custom)?;
+        if config.retention.max_periods.is_none() {
+            config.retention.max_periods = max_periods;
+        }
+        Ok(config)
+    }
 }
 
 impl FileLogConfig {
@@ -111,7 +269,27 @@ impl FileLogConfig {
     pub fn new<P: Into<std::path::PathBuf>>(path: P) -> Self {
         Self {
             path: path.into(),
+            plain_sizes: crate::PlainSizeUnit::Kilobytes,
             rotation: crate::RotationTrigger::Never,
+            retention: crate::Retention::default(),
+            compress: crate::Compression::None,
+            strategy: crate::RotationStrategy::CopyTruncate,
+            max_total_size: None,
+            naming: crate::FileNaming::default(),
+            symlink: None,
+            archive_dir: None,
+            header: None,
+            reopen_check: default_reopen_check(),
+            write_failure: crate::WriteFailurePolicy::Drop,
+            durability: crate::Durability::Flush,
+            buffer_size: default_buffer_size(),
+            multi_process: false,
+            oversized_records: crate::OversizedRecord::OwnFile,
+            rotate_on_start: false,
+            file_mode: None,
+            dir_mode: None,
+            owner: None,
+            group: None,
         }
     }
 
@@ -120,12 +298,122 @@ impl FileLogConfig {
         self.rotation = rotation;
         self
     }
+
+    /// Set retention policy for time-suffixed files
+    pub fn with_retention(mut self, retention: crate::Retention) -> Self {
+        self.retention = retention;
+        self
+    }
+
+    /// Set compression for rotated files
+    pub fn with_compression(mut self, compress: crate::Compression) -> Self {
+        self.compress = compress;
+        self
+    }
+
+    /// Set how the active file is moved aside during size-based rotation
+    pub fn with_strategy(mut self, strategy: crate::RotationStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Set maximum total size in bytes of the active file and all rotated files
+    pub fn with_max_total_size(mut self, max_total_size: u64) -> Self {
+        self.max_total_size = Some(max_total_size);
+        self
+    }
+
+    /// Set naming template for active and rotated files
+    pub fn with_naming(mut self, naming: crate::FileNaming) -> Self {
+        self.naming = naming;
+        self
+    }
+
+    /// Set how often to check whether the active file was moved or deleted
+    pub fn with_reopen_check(mut self, reopen_check: std::time::Duration) -> Self {
+        self.reopen_check = reopen_check;
+        self
+    }
+
+    /// Set what to do with records that can't be written
+    pub fn with_write_failure(mut self, write_failure: crate::WriteFailurePolicy) -> Self {
+        self.write_failure = write_failure;
+        self
+    }
+
+    /// Set how far records are pushed towards the disk when the file is flushed
+    pub fn with_durability(mut self, durability: crate::Durability) -> Self {
+        self.durability = durability;
+        self
+    }
+
+    /// Set the size of the write buffer in bytes, 0 to disable buffering
+    pub fn with_buffer_size(mut self, buffer_size: u64) -> Self {
+        self.buffer_size = buffer_size;
+        self
+    }
+
+    /// Enable or disable coordination with other processes writing to the same files
+    pub fn with_multi_process(mut self, multi_process: bool) -> Self {
+        self.multi_process = multi_process;
+        self
+    }
+
+    /// Set what to do with records larger than the maximum file size
+    pub fn with_oversized_records(mut self, oversized_records: crate::OversizedRecord) -> Self {
+        self.oversized_records = oversized_records;
+        self
+    }
+
+    /// Enable or disable rotating a non-empty active file when logging starts
+    pub fn with_rotate_on_start(mut self, rotate_on_start: bool) -> Self {
+        self.rotate_on_start = rotate_on_start;
+        self
+    }
+
+    /// Set the Unix mode of log files and rotated archives, e.g. `0o640`
+    pub fn with_file_mode(mut self, file_mode: u32) -> Self {
+        self.file_mode = Some(file_mode);
+        self
+    }
+
+    /// Set the Unix mode of directories created for the log files, e.g. `0o750`
+    pub fn with_dir_mode(mut self, dir_mode: u32) -> Self {
+        self.dir_mode = Some(dir_mode);
+        self
+    }
+
+    /// Set the user and group ids owning log files and created directories
+    pub fn with_owner(mut self, owner: Option<u32>, group: Option<u32>) -> Self {
+        self.owner = owner;
+        self.group = group;
+        self
+    }
+
+    /// Keep a symlink at `symlink` pointing at the active file
+    pub fn with_symlink<P: Into<std::path::PathBuf>>(mut self, symlink: P) -> Self {
+        self.symlink = Some(symlink.into());
+        self
+    }
+
+    /// Move rotated files to `archive_dir` instead of keeping them next to the active file
+    pub fn with_archive_dir<P: Into<std::path::PathBuf>>(mut self, archive_dir: P) -> Self {
+        self.archive_dir = Some(archive_dir.into());
+        self
+    }
+
+    /// Set the header written at the start of every new file
+    pub fn with_header(mut self, header: crate::FileHeader) -> Self {
+        self.header = Some(header);
+        self
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::path::PathBuf;
+    use std::time::Duration;
 
     #[test]
     fn test_log_config_new() {
@@ -137,6 +425,7 @@ mod tests {
         assert!(!config.target);
         assert!(!config.thread_ids);
         assert!(!config.thread_names);
+        assert_eq!(config.timezone, crate::Timezone::Local);
     }
 
     #[test]
@@ -198,6 +487,15 @@ mod tests {
         assert!(config.thread_names);
     }
 
+    #[test]
+    fn test_log_config_timezone() {
+        let config: LogConfig = serde_yaml::from_str("timezone: '+08:00'").unwrap();
+        assert_eq!(config.timezone, crate::Timezone::Fixed(8 * 3600));
+
+        let config = LogConfig::new().with_timezone(crate::Timezone::Utc);
+        assert_eq!(config.timezone, crate::Timezone::Utc);
+    }
+
     #[test]
     fn test_file_log_config_new() {
         let config = FileLogConfig::new("test.log");
@@ -213,6 +511,290 @@ mod tests {
         assert_eq!(config.rotation, crate::RotationTrigger::size(1024, 5));
     }
 
+    #[test]
+    fn test_file_log_config_retention_deserialize() {
+        let yaml = r#"
+path: app.log
+retention:
+  max_periods: 7
+  max_age: "30d"
+"#;
+        let config: FileLogConfig = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(config.retention.max_periods, Some(7));
+        assert_eq!(
+            config.retention.max_age,
+            Some(std::time::Duration::from_secs(30 * 24 * 60 * 60))
+        );
+
+        let config: FileLogConfig = serde_yaml::from_str("path: app.log").unwrap();
+        assert!(!config.retention.is_enabled());
+    }
+
+    #[test]
+    fn test_file_log_config_max_total_size() {
+        let config: FileLogConfig =
+            serde_yaml::from_str("path: app.log\nmax_total_size: 2G").unwrap();
+        assert_eq!(config.max_total_size, Some(2 * 1024 * 1024 * 1024));
+
+        let yaml = serde_yaml::to_string(&config).unwrap();
+        let parsed: FileLogConfig = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(parsed.max_total_size, config.max_total_size);
+
+        let config = FileLogConfig::new("app.log").with_max_total_size(1024);
+        assert_eq!(config.max_total_size, Some(1024));
+    }
+
+    #[test]
+    fn test_file_log_config_reopen_check() {
+        let config: FileLogConfig = serde_yaml::from_str("path: app.log").unwrap();
+        assert_eq!(config.reopen_check, std::time::Duration::from_secs(1));
+
+        let config: FileLogConfig =
+            serde_yaml::from_str("path: app.log\nreopen_check: 0s").unwrap();
+        assert_eq!(config.reopen_check, std::time::Duration::ZERO);
+
+        let yaml = serde_yaml::to_string(&config).unwrap();
+        let parsed: FileLogConfig = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(parsed.reopen_check, config.reopen_check);
+    }
+
+    #[test]
+    fn test_file_log_config_write_failure() {
+        let config: FileLogConfig = serde_yaml::from_str("path: app.log").unwrap();
+        assert_eq!(config.write_failure, crate::WriteFailurePolicy::Drop);
+
+        let config: FileLogConfig =
+            serde_yaml::from_str("path: app.log\nwrite_failure: retry").unwrap();
+        assert_eq!(config.write_failure, crate::WriteFailurePolicy::Retry);
+
+        let config =
+            FileLogConfig::new("app.log").with_write_failure(crate::WriteFailurePolicy::Stderr);
+        assert_eq!(config.write_failure, crate::WriteFailurePolicy::Stderr);
+    }
+
+    #[test]
+    fn test_file_log_config_durability() {
+        let config: FileLogConfig = serde_yaml::from_str("path: app.log").unwrap();
+        assert_eq!(config.durability, crate::Durability::Flush);
+        assert_eq!(config.buffer_size, 64 * 1024);
+
+        let config: FileLogConfig =
+            serde_yaml::from_str("path: app.log\ndurability: fsync_interval(5s)\nbuffer_size: 1M")
+                .unwrap();
+        assert_eq!(
+            config.durability,
+            crate::Durability::FsyncInterval(std::time::Duration::from_secs(5))
+        );
+        assert_eq!(config.buffer_size, 1024 * 1024);
+
+        let yaml = serde_yaml::to_string(&config).unwrap();
+        let parsed: FileLogConfig = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(parsed.durability, config.durability);
+        assert_eq!(parsed.buffer_size, config.buffer_size);
+
+        let config: FileLogConfig = serde_yaml::from_str("path: app.log\nbuffer_size: 0").unwrap();
+        assert_eq!(config.buffer_size, 0);
+    }
+
+    #[test]
+    fn test_file_log_config_oversized_records() {
+        let config: FileLogConfig = serde_yaml::from_str("path: app.log").unwrap();
+        assert_eq!(config.oversized_records, crate::OversizedRecord::OwnFile);
+
+        let config: FileLogConfig =
+            serde_yaml::from_str("path: app.log\noversized_records: truncate").unwrap();
+        assert_eq!(config.oversized_records, crate::OversizedRecord::Truncate);
+    }
+
+    #[test]
+    fn test_file_log_config_permissions() {
+        let config: FileLogConfig = serde_yaml::from_str(
+            "path: app.log\nfile_mode: '0640'\ndir_mode: 750\nowner: 1000\ngroup: 4",
+        )
+        .unwrap();
+        assert_eq!(config.file_mode, Some(0o640));
+        assert_eq!(config.dir_mode, Some(0o750));
+        assert_eq!(config.owner, Some(1000));
+        assert_eq!(config.group, Some(4));
+
+        let yaml = serde_yaml::to_string(&config).unwrap();
+        assert!(yaml.contains("file_mode: '0640'"), "{}", yaml);
+        let parsed: FileLogConfig = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(parsed.file_mode, config.file_mode);
+        assert_eq!(parsed.dir_mode, config.dir_mode);
+
+        assert!(serde_yaml::from_str::<FileLogConfig>("path: app.log\nfile_mode: 0689").is_err());
+    }
+
+    #[test]
+    fn test_file_log_config_plain_sizes() {
+        let yaml = r#"
+path: app.log
+rotation:
+  type: size
+  max_size: 1048576
+  max_files: 3
+max_total_size: 4194304
+buffer_size: 8192
+"#;
+        // Kilobytes unless configured otherwise, even when set after the sizes
+        let config: FileLogConfig = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(config.plain_sizes, crate::PlainSizeUnit::Kilobytes);
+        assert_eq!(config.rotation.max_size(), Some(1024 * 1024 * 1024));
+
+        let config: FileLogConfig =
+            serde_yaml::from_str(&format!("{}plain_sizes: bytes\n", yaml)).unwrap();
+        assert_eq!(config.rotation.max_size(), Some(1024 * 1024));
+        assert_eq!(config.max_total_size, Some(4 * 1024 * 1024));
+        assert_eq!(config.buffer_size, 8 * 1024);
+
+        // A trigger deserialized on its own always reads kilobytes
+        let trigger: crate::RotationTrigger =
+            serde_yaml::from_str("type: size\nmax_size: 1024").unwrap();
+        assert_eq!(trigger.max_size(), Some(1024 * 1024));
+
+        // Sizes with units don't depend on it
+        let config: FileLogConfig =
+            serde_yaml::from_str("path: app.log\nbuffer_size: 1.5M\nplain_sizes: bytes").unwrap();
+        assert_eq!(config.buffer_size, 1536 * 1024);
+
+        let error = serde_yaml::from_str::<FileLogConfig>("path: app.log\nbuffer_size: 1.5")
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("needs a unit"), "{}", error);
+    }
+
+    #[test]
+    fn test_file_log_config_archive_dir() {
+        let config: FileLogConfig = serde_yaml::from_str("path: logs/app.log").unwrap();
+        assert_eq!(config.archive_dir, None);
+        assert!(
+            !serde_yaml::to_string(&config)
+                .unwrap()
+                .contains("archive_dir")
+        );
+
+        let config: FileLogConfig =
+            serde_yaml::from_str("path: logs/app.log\narchive_dir: archive").unwrap();
+        assert_eq!(config.archive_dir, Some(PathBuf::from("archive")));
+        assert_eq!(
+            FileLogConfig::new("logs/app.log")
+                .with_archive_dir("archive")
+                .archive_dir,
+            config.archive_dir
+        );
+    }
+
+    #[test]
+    fn test_file_log_config_header() {
+        let config: FileLogConfig = serde_yaml::from_str(
+            "path: app.log\nheader:\n  json:\n    app: myapp\n    version: 1.2.3",
+        )
+        .unwrap();
+        assert_eq!(
+            config.header,
+            Some(crate::FileHeader::json([
+                ("app", "myapp"),
+                ("version", "1.2.3")
+            ]))
+        );
+
+        let config: FileLogConfig =
+            serde_yaml::from_str("path: app.log\nheader: '# started by {pid}'").unwrap();
+        assert_eq!(
+            config.header,
+            Some(crate::FileHeader::text("# started by {pid}"))
+        );
+    }
+
+    #[test]
+    fn test_file_log_config_documented_rotation() {
+        let yaml = "path: app.log\nrotation:\n  size:\n    max_size: 10M\n    max_backups: 5";
+        let config: FileLogConfig = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(
+            config.rotation,
+            crate::RotationTrigger::size(10 * 1024 * 1024, 5)
+        );
+        assert_eq!(config.retention, crate::Retention::default());
+
+        #[cfg(feature = "time")]
+        {
+            let toml = "path = \"app.log\"\n[rotation.daily]\nmax_backups = 7";
+            let config: FileLogConfig = toml::from_str(toml).unwrap();
+            assert_eq!(
+                config.rotation,
+                crate::RotationTrigger::time(crate::RotationPeriod::Daily)
+            );
+            assert_eq!(config.retention, crate::Retention::periods(7));
+
+            // An explicit retention policy takes precedence
+            let yaml = "path: app.log\nrotation: { daily: { max_backups: 7 } }\nretention: { max_periods: 3 }";
+            let config: FileLogConfig = serde_yaml::from_str(yaml).unwrap();
+            assert_eq!(config.retention, crate::Retention::periods(3));
+
+            let yaml = "path: app.log\nrotation: { type: time, period: daily, max_size: 10M }";
+            assert!(serde_yaml::from_str::<FileLogConfig>(yaml).is_err());
+        }
+    }
+
+    #[test]
+    fn test_log_config_serde_roundtrip() {
+        let mut file = FileLogConfig::new("logs/app.log")
+            .with_rotation_trigger(crate::RotationTrigger::size(100 * 1024 * 1024, 10))
+            .with_retention(crate::Retention::max_age(Duration::from_secs(
+                30 * 24 * 60 * 60,
+            )))
+            .with_max_total_size(2 * 1024 * 1024 * 1024)
+            .with_reopen_check(Duration::from_millis(250))
+            .with_durability(crate::Durability::FsyncInterval(Duration::from_secs(5)))
+            .with_buffer_size(1000)
+            .with_header(crate::FileHeader::json([("app", "myapp")]))
+            .with_archive_dir("archive")
+            .with_file_mode(0o640)
+            .with_owner(Some(1000), None);
+        file.plain_sizes = crate::PlainSizeUnit::Bytes;
+        let config = LogConfig::new()
+            .with_console(true)
+            .with_level("debug".to_string())
+            .with_format("json".to_string())
+            .with_file(file)
+            .with_thread_ids(true)
+            .with_timezone(crate::Timezone::Utc);
+
+        let yaml = serde_yaml::to_string(&config).unwrap();
+        assert_eq!(
+            serde_yaml::from_str::<LogConfig>(&yaml).unwrap(),
+            config,
+            "{}",
+            yaml
+        );
+
+        let toml = toml::to_string(&config).unwrap();
+        assert_eq!(
+            toml::from_str::<LogConfig>(&toml).unwrap(),
+            config,
+            "{}",
+            toml
+        );
+
+        let json = serde_json::to_string(&config).unwrap();
+        assert_eq!(
+            serde_json::from_str::<LogConfig>(&json).unwrap(),
+            config,
+            "{}",
+            json
+        );
+
+        let config = LogConfig::new().with_file(FileLogConfig::new("app.log"));
+        let yaml = serde_yaml::to_string(&config).unwrap();
+        assert_eq!(
+            serde_yaml::from_str::<LogConfig>(&yaml).unwrap(),
+            config,
+            "{}",
+            yaml
+        );
+    }
+
     #[test]
     fn test_default_functions() {
         assert_eq!(super::default_log_level(), "info");
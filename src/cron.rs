@@ -0,0 +1,405 @@
+use std::fmt;
+use std::str::FromStr;
+
+#[cfg(feature = "time")]
+use time::{Date, OffsetDateTime};
+
+use crate::{Error, Result};
+
+/// How far to search for the previous or next firing time. Long enough for the
+/// rarest valid schedules, such as February 29th.
+#[cfg(feature = "time")]
+const SEARCH_DAYS: usize = 3000;
+
+/// Days in each month, allowing February 29th.
+const MAX_DAYS_IN_MONTH: [u32; 12] = [31, 29, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
+
+/// A cron-style rotation schedule.
+///
+/// Uses the standard five fields `minute hour day-of-month month day-of-week`,
+/// e.g. `0 3 * * *` for 03:00 every day. Each field accepts `*`, numbers,
+/// ranges (`1-5`), lists (`1,15`) and steps (`*/15`, `0-30/10`). Day-of-week
+/// runs from `0` (Sunday) to `6`, with `7` also meaning Sunday.
+///
+/// As in cron, when both day-of-month and day-of-week are restricted, a day
+/// matches if either field matches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u32,
+    days: u32,
+    months: u16,
+    weekdays: u8,
+}
+
+/// Valid values and bit masks of a schedule field.
+struct Field {
+    name: &'static str,
+    min: u32,
+    max: u32,
+}
+
+const MINUTE: Field = Field {
+    name: "minute",
+    min: 0,
+    max: 59,
+};
+const HOUR: Field = Field {
+    name: "hour",
+    min: 0,
+    max: 23,
+};
+const DAY: Field = Field {
+    name: "day-of-month",
+    min: 1,
+    max: 31,
+};
+const MONTH: Field = Field {
+    name: "month",
+    min: 1,
+    max: 12,
+};
+const WEEKDAY: Field = Field {
+    name: "day-of-week",
+    min: 0,
+    max: 7,
+};
+
+impl Field {
+    /// Mask with every valid value set.
+    fn all(&self) -> u64 {
+        (self.min..=self.max).fold(0, |mask, v| mask | (1 << v))
+    }
+
+    /// Parse a field expression into a bit mask.
+    fn parse(&self, expr: &str) -> std::result::Result<u64, String> {
+        let mut mask = 0;
+        for item in expr.split(',') {
+            let (range, step) = match item.split_once('/') {
+                Some((range, step)) => {
+                    let step: u32 = step
+                        .parse()
+                        .ok()
+                        .filter(|&step| step > 0)
+                        .ok_or_else(|| format!("invalid {} step `{}`", self.name, step))?;
+                    (range, step)
+                }
+                None => (item, 1),
+            };
+
+            let (start, end) = if range == "*" {
+                (self.min, self.max)
+            } else if let Some((start, end)) = range.split_once('-') {
+                (self.value(start)?, self.value(end)?)
+            } else {
+                let value = self.value(range)?;
+                // `5/10` means from 5 to the end, like `5-59/10`
+                (value, if step > 1 { self.max } else { value })
+            };
+            if start > end {
+                return Err(format!("invalid {} range `{}`", self.name, range));
+            }
+
+            mask |= (start..=end)
+                .step_by(step as usize)
+                .fold(0, |mask, v| mask | (1 << v));
+        }
+        Ok(mask)
+    }
+
+    /// Parse a single value within the field's bounds.
+    fn value(&self, s: &str) -> std::result::Result<u32, String> {
+        s.parse()
+            .ok()
+            .filter(|v| (self.min..=self.max).contains(v))
+            .ok_or_else(|| {
+                format!(
+                    "invalid {} `{}`, expected {}-{}",
+                    self.name, s, self.min, self.max
+                )
+            })
+    }
+
+    /// Format a bit mask as a field expression, using `*` for every value.
+    fn format(&self, mask: u64) -> String {
+        if mask == self.all() {
+            return "*".to_string();
+        }
+        if let Some(step) = (2..=self.max).find(|&step| {
+            mask == (self.min..=self.max)
+                .step_by(step as usize)
+                .fold(0, |mask, v| mask | (1 << v))
+        }) {
+            return format!("*/{}", step);
+        }
+
+        let mut items = Vec::new();
+        let mut v = self.min;
+        while v <= self.max {
+            if mask & (1 << v) == 0 {
+                v += 1;
+                continue;
+            }
+            let start = v;
+            while v < self.max && mask & (1 << (v + 1)) != 0 {
+                v += 1;
+            }
+            items.push(match v - start {
+                0 => start.to_string(),
+                1 => format!("{},{}", start, v),
+                _ => format!("{}-{}", start, v),
+            });
+            v += 1;
+        }
+        items.join(",")
+    }
+}
+
+impl CronSchedule {
+    /// Parse a schedule from a five-field cron expression.
+    pub fn new(expr: &str) -> Result<Self> {
+        let invalid =
+            |reason: String| Error::Config(format!("invalid schedule `{}`: {}", expr, reason));
+
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(invalid(format!(
+                "expected 5 fields (minute hour day-of-month month day-of-week), got {}",
+                fields.len()
+            )));
+        };
+
+        let mut weekdays = WEEKDAY.parse(weekday).map_err(invalid)?;
+        // Sunday can be written as 0 or 7
+        if weekdays & (1 << 7) != 0 {
+            weekdays = (weekdays | 1) & !(1 << 7);
+        }
+
+        let schedule = Self {
+            minutes: MINUTE.parse(minute).map_err(invalid)?,
+            hours: HOUR.parse(hour).map_err(invalid)? as u32,
+            days: DAY.parse(day).map_err(invalid)? as u32,
+            months: MONTH.parse(month).map_err(invalid)? as u16,
+            weekdays: weekdays as u8,
+        };
+
+        // With the day-of-week unrestricted, the days must exist in some month
+        if !schedule.weekdays_restricted()
+            && !(1..=12).any(|m| {
+                schedule.months & (1 << m) != 0
+                    && (1..=MAX_DAYS_IN_MONTH[m - 1]).any(|d| schedule.days & (1 << d) != 0)
+            })
+        {
+            return Err(invalid("the schedule never fires".to_string()));
+        }
+
+        Ok(schedule)
+    }
+
+    /// Check if the day-of-month field excludes any day.
+    #[cfg(feature = "time")]
+    fn days_restricted(&self) -> bool {
+        u64::from(self.days) != DAY.all()
+    }
+
+    /// Check if the day-of-week field excludes any day.
+    fn weekdays_restricted(&self) -> bool {
+        self.weekdays != 0x7f
+    }
+
+    /// Check if the schedule only fires on the hour.
+    #[cfg(feature = "time")]
+    pub(crate) fn is_hourly(&self) -> bool {
+        self.minutes == 1
+    }
+
+    /// Check if the schedule only fires at midnight.
+    #[cfg(feature = "time")]
+    pub(crate) fn is_daily(&self) -> bool {
+        self.is_hourly() && self.hours == 1
+    }
+
+    /// Check if the schedule fires on `date`.
+    #[cfg(feature = "time")]
+    fn matches_date(&self, date: Date) -> bool {
+        if self.months & (1 << u8::from(date.month())) == 0 {
+            return false;
+        }
+        let day = self.days & (1 << date.day()) != 0;
+        let weekday = self.weekdays & (1 << date.weekday().number_days_from_sunday()) != 0;
+        match (self.days_restricted(), self.weekdays_restricted()) {
+            (true, true) => day || weekday,
+            _ => day && weekday,
+        }
+    }
+
+    /// Get the latest firing time of the day at or before `hour:minute`.
+    #[cfg(feature = "time")]
+    fn last_time_until(&self, hour: u8, minute: u8) -> Option<(u8, u8)> {
+        (0..=hour).rev().find_map(|h| {
+            if self.hours & (1 << h) == 0 {
+                return None;
+            }
+            let limit = if h == hour { minute } else { 59 };
+            let minutes = self.minutes & (u64::MAX >> (63 - limit));
+            (minutes != 0).then(|| (h, 63 - minutes.leading_zeros() as u8))
+        })
+    }
+
+    /// Get the earliest firing time of the day at or after `hour:minute`.
+    #[cfg(feature = "time")]
+    fn first_time_from(&self, hour: u8, minute: u8) -> Option<(u8, u8)> {
+        (hour..24).find_map(|h| {
+            if self.hours & (1 << h) == 0 {
+                return None;
+            }
+            let limit = if h == hour { minute } else { 0 };
+            let minutes = self.minutes & (u64::MAX << limit);
+            (minutes != 0).then(|| (h, minutes.trailing_zeros() as u8))
+        })
+    }
+
+    /// Get the latest firing time at or before `at`, in the offset of `at`.
+    #[cfg(feature = "time")]
+    pub fn previous(&self, at: OffsetDateTime) -> Option<OffsetDateTime> {
+        let mut date = at.date();
+        let (mut hour, mut minute) = (at.hour(), at.minute());
+        for _ in 0..SEARCH_DAYS {
+            if self.matches_date(date)
+                && let Some((h, m)) = self.last_time_until(hour, minute)
+            {
+                return Some(date.with_hms(h, m, 0).ok()?.assume_offset(at.offset()));
+            }
+            date = date.previous_day()?;
+            (hour, minute) = (23, 59);
+        }
+        None
+    }
+
+    /// Get the earliest firing time strictly after `after`, in the offset of `after`.
+    #[cfg(feature = "time")]
+    pub fn next(&self, after: OffsetDateTime) -> Option<OffsetDateTime> {
+        let mut date = after.date();
+        let (mut hour, mut minute) = match (after.hour(), after.minute()) {
+            (23, 59) => {
+                date = date.next_day()?;
+                (0, 0)
+            }
+            (h, 59) => (h + 1, 0),
+            (h, m) => (h, m + 1),
+        };
+        for _ in 0..SEARCH_DAYS {
+            if self.matches_date(date)
+                && let Some((h, m)) = self.first_time_from(hour, minute)
+            {
+                return Some(date.with_hms(h, m, 0).ok()?.assume_offset(after.offset()));
+            }
+            date = date.next_day()?;
+            (hour, minute) = (0, 0);
+        }
+        None
+    }
+}
+
+impl FromStr for CronSchedule {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::new(s)
+    }
+}
+
+impl fmt::Display for CronSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {} {}",
+            MINUTE.format(self.minutes),
+            HOUR.format(u64::from(self.hours)),
+            DAY.format(u64::from(self.days)),
+            MONTH.format(u64::from(self.months)),
+            Field { max: 6, ..WEEKDAY }.format(u64::from(self.weekdays)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cron_schedule_parse() {
+        let schedule = CronSchedule::new("0 3 * * *").unwrap();
+        assert_eq!(schedule.to_string(), "0 3 * * *");
+
+        assert_eq!(
+            CronSchedule::new("*/15 0-6/2 1,15 * 1-5")
+                .unwrap()
+                .to_string(),
+            "*/15 0,2,4,6 1,15 * 1-5"
+        );
+        assert_eq!(
+            CronSchedule::new("0 0 * * 7").unwrap(),
+            CronSchedule::new("0 0 * * 0").unwrap()
+        );
+
+        for invalid in [
+            "",
+            "0 3 * *",
+            "60 * * * *",
+            "* 24 * * *",
+            "* * 0 * *",
+            "* * * 13 *",
+            "* * * * 8",
+            "5-1 * * * *",
+            "*/0 * * * *",
+            "0 0 31 2 *",
+            "a * * * *",
+        ] {
+            assert!(CronSchedule::new(invalid).is_err(), "{:?}", invalid);
+        }
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn test_cron_schedule_previous_and_next() {
+        use time::macros::datetime;
+
+        let daily = CronSchedule::new("0 3 * * *").unwrap();
+        assert!(daily.is_hourly() && !daily.is_daily());
+        assert!(CronSchedule::new("0 0 * * *").unwrap().is_daily());
+        assert_eq!(
+            daily.previous(datetime!(2026-01-09 02:59 UTC)),
+            Some(datetime!(2026-01-08 3:00 UTC))
+        );
+        assert_eq!(
+            daily.previous(datetime!(2026-01-09 03:00 UTC)),
+            Some(datetime!(2026-01-09 3:00 UTC))
+        );
+        assert_eq!(
+            daily.next(datetime!(2026-01-09 03:00 UTC)),
+            Some(datetime!(2026-01-10 3:00 UTC))
+        );
+        assert_eq!(
+            daily.next(datetime!(2026-12-31 23:59:30 UTC)),
+            Some(datetime!(2027-01-01 3:00 UTC))
+        );
+
+        // Day-of-month or day-of-week: the 13th, or any Friday
+        let either = CronSchedule::new("30 12 13 * 5").unwrap();
+        assert_eq!(
+            either.next(datetime!(2026-02-07 0:00 UTC)),
+            Some(datetime!(2026-02-13 12:30 UTC))
+        );
+        assert_eq!(
+            either.next(datetime!(2026-02-13 12:30 UTC)),
+            Some(datetime!(2026-02-20 12:30 UTC))
+        );
+
+        let leap = CronSchedule::new("0 0 29 2 *").unwrap();
+        assert_eq!(
+            leap.previous(datetime!(2026-01-09 0:00 UTC)),
+            Some(datetime!(2024-02-29 0:00 UTC))
+        );
+    }
+}
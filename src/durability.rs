@@ -0,0 +1,136 @@
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+
+use crate::{Error, Result};
+
+/// How far buffered records are pushed towards the disk when the log file is
+/// flushed.
+///
+/// In config, written as `none`, `flush`, `fsync_on_flush`, or
+/// `fsync_interval(5s)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Durability {
+    /// Only write out the buffer when it's full and on rotation.
+    ///
+    /// The fastest mode; records still buffered when the process exits or
+    /// crashes may be lost.
+    None,
+    /// Write out the buffer to the operating system on every flush.
+    ///
+    /// Records survive a crash of the process, but not of the machine.
+    #[default]
+    Flush,
+    /// Write out the buffer and sync the file to disk on every flush.
+    FsyncOnFlush,
+    /// Write out the buffer on every flush, and sync the file to disk on a
+    /// flush at least this long after the previous sync.
+    FsyncInterval(Duration),
+}
+
+impl FromStr for Durability {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        match s.to_ascii_lowercase().as_str() {
+            "none" => return Ok(Self::None),
+            "flush" => return Ok(Self::Flush),
+            "fsync_on_flush" => return Ok(Self::FsyncOnFlush),
+            _ => {}
+        }
+
+        s.strip_prefix("fsync_interval(")
+            .and_then(|rest| rest.strip_suffix(')'))
+            .and_then(|interval| crate::rotation::parse_duration(interval).ok())
+            .map(Self::FsyncInterval)
+            .ok_or_else(|| {
+                Error::Config(format!(
+                    "invalid durability `{}`: expected none, flush, fsync_on_flush, or fsync_interval(<duration>)",
+                    s
+                ))
+            })
+    }
+}
+
+impl fmt::Display for Durability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::None => f.write_str("none"),
+            Self::Flush => f.write_str("flush"),
+            Self::FsyncOnFlush => f.write_str("fsync_on_flush"),
+            Self::FsyncInterval(interval) => write!(
+                f,
+                "fsync_interval({})",
+                crate::rotation::format_duration(*interval)
+            ),
+        }
+    }
+}
+
+impl Serialize for Durability {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Durability {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_durability_from_str() {
+        assert_eq!("none".parse::<Durability>().unwrap(), Durability::None);
+        assert_eq!("Flush".parse::<Durability>().unwrap(), Durability::Flush);
+        assert_eq!(
+            "fsync_on_flush".parse::<Durability>().unwrap(),
+            Durability::FsyncOnFlush
+        );
+        assert_eq!(
+            "fsync_interval(5s)".parse::<Durability>().unwrap(),
+            Durability::FsyncInterval(Duration::from_secs(5))
+        );
+        assert_eq!(
+            "fsync_interval(2m)".parse::<Durability>().unwrap(),
+            Durability::FsyncInterval(Duration::from_secs(120))
+        );
+
+        for invalid in [
+            "",
+            "fsync",
+            "fsync_interval",
+            "fsync_interval()",
+            "fsync_interval(5x)",
+        ] {
+            assert!(invalid.parse::<Durability>().is_err(), "{:?}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_durability_serde_roundtrip() {
+        for input in ["none", "flush", "fsync_on_flush", "fsync_interval(30s)"] {
+            let durability: Durability = serde_yaml::from_str(input).unwrap();
+            assert_eq!(durability.to_string(), input);
+            let yaml = serde_yaml::to_string(&durability).unwrap();
+            assert_eq!(
+                serde_yaml::from_str::<Durability>(&yaml).unwrap(),
+                durability
+            );
+        }
+    }
+}
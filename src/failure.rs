@@ -0,0 +1,236 @@
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+/// What to do with a record that can't be written to the log file, e.g.
+/// because the disk is full or the file is no longer writable.
+///
+/// Failures are reported on stderr when they start, at most every
+/// 10 seconds while they last, and when writing recovers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WriteFailurePolicy {
+    /// Drop the record and count it.
+    #[default]
+    Drop,
+    /// Write the record to stderr instead.
+    Stderr,
+    /// Drop the record, then reopen the file and try again after a backoff
+    /// that doubles on every failure, from 100ms up to 30s.
+    ///
+    /// Records arriving before the next attempt are dropped and counted.
+    Retry,
+}
+
+impl WriteFailurePolicy {
+    /// What happens to records while writing fails, for diagnostics.
+    fn action(self) -> &'static str {
+        match self {
+            Self::Drop => "dropping records",
+            Self::Stderr => "writing records to stderr",
+            Self::Retry => "dropping records until a retry succeeds",
+        }
+    }
+}
+
+/// How to handle the next record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Attempt {
+    /// Write the record to the open file.
+    Write,
+    /// Reopen the file, then write the record.
+    Reopen,
+    /// Don't try to write the record, the file is still backing off.
+    Skip,
+}
+
+/// Tracks write failures: applies the policy to failed records, counts
+/// dropped records and reports failures without flooding stderr.
+#[derive(Debug)]
+pub(crate) struct WriteFailures {
+    policy: WriteFailurePolicy,
+    dropped: AtomicU64,
+    state: Mutex<FailureState>,
+}
+
+/// The current failure streak, if any.
+#[derive(Debug, Default)]
+struct FailureState {
+    /// When the current streak started.
+    since: Option<Instant>,
+    /// When the streak was last reported.
+    reported_at: Option<Instant>,
+    /// Dropped records at the start of the streak.
+    dropped_before: u64,
+    /// Delay before the next retry.
+    backoff: Duration,
+    /// When to try writing again.
+    retry_at: Option<Instant>,
+}
+
+impl WriteFailures {
+    /// Minimum time between reports of an ongoing failure.
+    const REPORT_INTERVAL: Duration = Duration::from_secs(10);
+    /// Delay before the first retry.
+    const MIN_BACKOFF: Duration = Duration::from_millis(100);
+    /// Longest delay between retries.
+    const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+    pub(crate) fn new(policy: WriteFailurePolicy) -> Self {
+        Self {
+            policy,
+            dropped: AtomicU64::new(0),
+            state: Mutex::new(FailureState::default()),
+        }
+    }
+
+    /// Number of records dropped so far.
+    pub(crate) fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Decide how to handle the next record.
+    pub(crate) fn next_attempt(&self) -> Attempt {
+        let state = self.state.lock().unwrap();
+        match state.retry_at {
+            Some(retry_at) if Instant::now() < retry_at => Attempt::Skip,
+            Some(_) => Attempt::Reopen,
+            None => Attempt::Write,
+        }
+    }
+
+    /// Record a successful write, reporting the recovery from a failure streak.
+    pub(crate) fn succeeded(&self, path: &Path) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(since) = state.since {
+            eprintln!(
+                "lazylog: writing to {} recovered after {:.1}s ({} records dropped)",
+                path.display(),
+                since.elapsed().as_secs_f64(),
+                self.dropped() - state.dropped_before
+            );
+            *state = FailureState::default();
+        }
+    }
+
+    /// Apply the policy to records that failed to be written with `error`.
+    pub(crate) fn failed(&self, path: &Path, error: &io::Error, buf: &[u8]) {
+        let records = buf.split_inclusive(|&b| b == b'\n').count() as u64;
+        let mut state = self.state.lock().unwrap();
+        let now = Instant::now();
+        if state.since.is_none() {
+            state.since = Some(now);
+            state.dropped_before = self.dropped();
+        }
+
+        match self.policy {
+            WriteFailurePolicy::Drop => self.drop_records(records),
+            WriteFailurePolicy::Stderr => {
+                if io::stderr().write_all(buf).is_err() {
+                    self.drop_records(records);
+                }
+            }
+            WriteFailurePolicy::Retry => {
+                self.drop_records(records);
+                state.backoff = (state.backoff * 2).clamp(Self::MIN_BACKOFF, Self::MAX_BACKOFF);
+                state.retry_at = Some(now + state.backoff);
+            }
+        }
+
+        match state.reported_at {
+            None => eprintln!(
+                "lazylog: failed to write to {}: {}; {}",
+                path.display(),
+                error,
+                self.policy.action()
+            ),
+            Some(reported_at) if now.duration_since(reported_at) >= Self::REPORT_INTERVAL => {
+                eprintln!(
+                    "lazylog: still failing to write to {}: {} ({} records dropped so far)",
+                    path.display(),
+                    error,
+                    self.dropped() - state.dropped_before
+                )
+            }
+            Some(_) => return,
+        }
+        state.reported_at = Some(now);
+    }
+
+    /// Drop a record that arrived while backing off.
+    pub(crate) fn skipped(&self) {
+        self.drop_records(1);
+    }
+
+    fn drop_records(&self, records: u64) {
+        self.dropped.fetch_add(records, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_failure_policy_serde() {
+        for (input, policy) in [
+            ("drop", WriteFailurePolicy::Drop),
+            ("stderr", WriteFailurePolicy::Stderr),
+            ("retry", WriteFailurePolicy::Retry),
+        ] {
+            assert_eq!(
+                serde_yaml::from_str::<WriteFailurePolicy>(input).unwrap(),
+                policy
+            );
+            assert_eq!(serde_yaml::to_string(&policy).unwrap().trim(), input);
+        }
+        assert!(serde_yaml::from_str::<WriteFailurePolicy>("ignore").is_err());
+    }
+
+    #[test]
+    fn test_write_failures_drop() {
+        let failures = WriteFailures::new(WriteFailurePolicy::Drop);
+        let error = io::Error::other("disk full");
+
+        failures.failed(Path::new("app.log"), &error, b"record\n");
+        failures.failed(Path::new("app.log"), &error, b"record\n");
+        assert_eq!(failures.dropped(), 2);
+        // Buffered records failing together are counted one by one
+        failures.failed(Path::new("app.log"), &error, b"one\ntwo\npartial");
+        failures.failed(Path::new("app.log"), &error, b"");
+        assert_eq!(failures.dropped(), 5);
+        assert_eq!(failures.next_attempt(), Attempt::Write);
+
+        failures.succeeded(Path::new("app.log"));
+        assert!(failures.state.lock().unwrap().since.is_none());
+    }
+
+    #[test]
+    fn test_write_failures_retry_backoff() {
+        let failures = WriteFailures::new(WriteFailurePolicy::Retry);
+        let error = io::Error::other("disk full");
+
+        failures.failed(Path::new("app.log"), &error, b"record\n");
+        assert_eq!(failures.next_attempt(), Attempt::Skip);
+        failures.skipped();
+        assert_eq!(failures.dropped(), 2);
+
+        failures.failed(Path::new("app.log"), &error, b"record\n");
+        assert_eq!(
+            failures.state.lock().unwrap().backoff,
+            WriteFailures::MIN_BACKOFF * 2
+        );
+
+        // Once the backoff expired, the file is reopened
+        failures.state.lock().unwrap().retry_at = Some(Instant::now());
+        assert_eq!(failures.next_attempt(), Attempt::Reopen);
+
+        failures.succeeded(Path::new("app.log"));
+        assert_eq!(failures.next_attempt(), Attempt::Write);
+        assert_eq!(failures.dropped(), 3);
+    }
+}
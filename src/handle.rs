@@ -0,0 +1,68 @@
+use crate::{Result, RotatingWriter};
+
+/// A handle to the logging system, returned by
+/// [`LogBuilder::init_with_handle`](crate::LogBuilder::init_with_handle).
+///
+/// The handle can be cloned and kept anywhere, e.g. in a signal handler.
+#[derive(Debug, Clone, Default)]
+pub struct LoggingHandle {
+    writer: Option<RotatingWriter>,
+}
+
+impl LoggingHandle {
+    /// Create a handle for the given file writer, if any.
+    pub(crate) fn new(writer: Option<RotatingWriter>) -> Self {
+        Self { writer }
+    }
+
+    /// Number of records dropped because they couldn't be written to the log file.
+    pub fn dropped_records(&self) -> u64 {
+        self.writer
+            .as_ref()
+            .map_or(0, RotatingWriter::dropped_records)
+    }
+
+    /// Rotate the active log file now, regardless of the rotation trigger.
+    ///
+    /// Does nothing without file logging or when the file has no records yet.
+    pub fn rotate_now(&self) -> Result<()> {
+        match &self.writer {
+            Some(writer) => writer.rotate_now().map_err(Into::into),
+            None => Ok(()),
+        }
+    }
+
+    /// Close the active log file and open it again by name.
+    ///
+    /// Call this after an external tool such as logrotate moved or deleted
+    /// the file, typically on `SIGHUP`. Does nothing without file logging.
+    pub fn reopen(&self) -> Result<()> {
+        match &self.writer {
+            Some(writer) => writer.reopen().map_err(Into::into),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_handle_without_file() {
+        assert!(LoggingHandle::default().reopen().is_ok());
+        assert!(LoggingHandle::default().rotate_now().is_ok());
+    }
+
+    #[test]
+    fn test_handle_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.log");
+        let writer = RotatingWriter::from_config(&crate::FileLogConfig::new(&path)).unwrap();
+        let handle = LoggingHandle::new(Some(writer));
+
+        std::fs::remove_file(&path).unwrap();
+        handle.reopen().unwrap();
+        assert!(path.exists());
+    }
+}
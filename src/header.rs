@@ -0,0 +1,325 @@
+use std::collections::BTreeMap;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::FileLogConfig;
+
+/// A header written at the start of every new log file, so that each file,
+/// once rotated, still tells where it came from.
+///
+/// The header is only written to files created empty: a file resumed after a
+/// restart keeps its original header. Its bytes count toward the size limit,
+/// but it isn't a record for the record count limit.
+///
+/// In config, a text header is written as a string, and a JSON header as a
+/// map under `json`:
+///
+/// ```yaml
+/// header: "# {app} on {hostname}, pid {pid}, continues {previous}"
+/// # or
+/// header:
+///   json: { app: myapp, version: 1.2.3 }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum FileHeader {
+    /// A line of text with placeholders, e.g. a comment for text logs.
+    ///
+    /// Supported placeholders:
+    /// - `{app}`: file name of the running executable
+    /// - `{hostname}`: name of the host
+    /// - `{pid}`: id of the process
+    /// - `{file}`: path of the new file
+    /// - `{previous}`: path of the file it replaces, empty if none
+    /// - `{config}`: the file logging config, as JSON
+    ///
+    /// Other text, including braces, is written as is. A newline is added
+    /// unless the text ends with one.
+    Text(String),
+    /// A JSON record with the given fields, e.g. the app name and version,
+    /// followed by `hostname`, `pid`, `file`, `previous` and `config` as for
+    /// [`Text`](Self::Text). Given fields take precedence.
+    Json {
+        /// Fields to include in the record.
+        json: BTreeMap<String, String>,
+    },
+}
+
+impl FileHeader {
+    /// Create a text header.
+    pub fn text(template: impl Into<String>) -> Self {
+        Self::Text(template.into())
+    }
+
+    /// Create a JSON header with the given fields.
+    pub fn json<K, V>(fields: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        Self::Json {
+            json: fields
+                .into_iter()
+                .map(|(k, v)| (k.into(), v.into()))
+                .collect(),
+        }
+    }
+}
+
+/// Placeholders of text headers.
+const PLACEHOLDERS: [&str; 6] = [
+    "{app}",
+    "{hostname}",
+    "{pid}",
+    "{file}",
+    "{previous}",
+    "{config}",
+];
+
+/// Fields every JSON header has besides the given ones.
+const JSON_FIELDS: [&str; 5] = ["hostname", "pid", "file", "previous", "config"];
+
+/// Longest header recognized at the start of a resumed file.
+const MAX_HEADER_LEN: u64 = 64 * 1024;
+
+/// Renders the header of a writer, with the details that don't change
+/// between files gathered once.
+#[derive(Debug)]
+pub(crate) struct HeaderWriter {
+    header: FileHeader,
+    app: String,
+    hostname: String,
+    pid: u32,
+    config: serde_json::Value,
+}
+
+impl HeaderWriter {
+    pub(crate) fn new(header: FileHeader, config: &FileLogConfig) -> Self {
+        let app = std::env::current_exe()
+            .ok()
+            .and_then(|exe| Some(exe.file_name()?.to_string_lossy().into_owned()))
+            .unwrap_or_default();
+        Self {
+            header,
+            app,
+            hostname: hostname(),
+            pid: std::process::id(),
+            config: serde_json::to_value(config).unwrap_or_default(),
+        }
+    }
+
+    /// Render the header of the new file at `file`, which replaces `previous`.
+    pub(crate) fn render(&self, file: &Path, previous: Option<&Path>) -> Vec<u8> {
+        let file = file.display().to_string();
+        let previous = previous.map(|path| path.display().to_string());
+
+        let mut header = match &self.header {
+            FileHeader::Text(template) => template
+                .replace("{app}", &self.app)
+                .replace("{hostname}", &self.hostname)
+                .replace("{pid}", &self.pid.to_string())
+                .replace("{file}", &file)
+                .replace("{previous}", previous.as_deref().unwrap_or_default())
+                .replace("{config}", &self.config.to_string()),
+            FileHeader::Json { json } => {
+                let mut record = serde_json::Map::new();
+                record.insert("hostname".into(), self.hostname.clone().into());
+                record.insert("pid".into(), self.pid.into());
+                record.insert("file".into(), file.into());
+                record.insert("previous".into(), previous.into());
+                record.insert("config".into(), self.config.clone());
+                for (key, value) in json {
+                    record.insert(key.clone(), value.clone().into());
+                }
+                serde_json::Value::Object(record).to_string()
+            }
+        };
+        if !header.ends_with('\n') {
+            header.push('\n');
+        }
+        header.into_bytes()
+    }
+
+    /// Get the length of the header at the start of `file`, if it begins with
+    /// one rendered from this header, e.g. when a file is resumed.
+    ///
+    /// Placeholders match any text within a line, so a header naming another
+    /// process or file is recognized too.
+    pub(crate) fn find_in(&self, file: impl Read) -> io::Result<Option<u64>> {
+        let template = match &self.header {
+            FileHeader::Text(template) if template.ends_with('\n') => template.clone(),
+            FileHeader::Text(template) => format!("{}\n", template),
+            FileHeader::Json { .. } => "\n".to_string(),
+        };
+
+        let mut reader = BufReader::new(file.take(MAX_HEADER_LEN));
+        let mut start = Vec::new();
+        for _ in 0..template.matches('\n').count() {
+            if reader.read_until(b'\n', &mut start)? == 0 || !start.ends_with(b"\n") {
+                return Ok(None);
+            }
+        }
+        let Ok(start_text) = std::str::from_utf8(&start) else {
+            return Ok(None);
+        };
+
+        let found = match &self.header {
+            FileHeader::Text(_) => template
+                .lines()
+                .zip(start_text.lines())
+                .all(|(template, line)| matches_template(line, template)),
+            FileHeader::Json { json } => match serde_json::from_str(start_text) {
+                Ok(serde_json::Value::Object(record)) => {
+                    JSON_FIELDS.iter().all(|field| record.contains_key(*field))
+                        && json.iter().all(|(key, value)| {
+                            record.get(key).and_then(serde_json::Value::as_str) == Some(value)
+                        })
+                }
+                _ => false,
+            },
+        };
+        Ok(found.then_some(start.len() as u64))
+    }
+}
+
+/// Check if `line` is the one-line `template` with its placeholders filled in.
+fn matches_template(line: &str, template: &str) -> bool {
+    // The literal text between placeholders
+    let mut literals = Vec::new();
+    let mut rest = template;
+    while let Some((start, placeholder)) = PLACEHOLDERS
+        .iter()
+        .filter_map(|placeholder| Some((rest.find(placeholder)?, placeholder)))
+        .min()
+    {
+        literals.push(&rest[..start]);
+        rest = &rest[start + placeholder.len()..];
+    }
+    literals.push(rest);
+
+    let Some(mut remaining) = line.strip_prefix(literals[0]) else {
+        return false;
+    };
+    let Some((last, middle)) = literals[1..].split_last() else {
+        return remaining.is_empty();
+    };
+    for literal in middle {
+        match remaining.find(literal) {
+            Some(start) => remaining = &remaining[start + literal.len()..],
+            None => return false,
+        }
+    }
+    remaining.ends_with(last)
+}
+
+/// Get the name of the host, or an empty string if it's unknown.
+fn hostname() -> String {
+    #[cfg(unix)]
+    for path in ["/proc/sys/kernel/hostname", "/etc/hostname"] {
+        if let Ok(name) = std::fs::read_to_string(path)
+            && !name.trim().is_empty()
+        {
+            return name.trim().to_string();
+        }
+    }
+    std::env::var("HOSTNAME")
+        .or_else(|_| std::env::var("COMPUTERNAME"))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_header_serde() {
+        let header: FileHeader = serde_yaml::from_str("'# {app} pid {pid}'").unwrap();
+        assert_eq!(header, FileHeader::text("# {app} pid {pid}"));
+
+        let header: FileHeader =
+            serde_yaml::from_str("json: { app: myapp, version: 1.2.3 }").unwrap();
+        assert_eq!(
+            header,
+            FileHeader::json([("app", "myapp"), ("version", "1.2.3")])
+        );
+        let yaml = serde_yaml::to_string(&header).unwrap();
+        assert_eq!(serde_yaml::from_str::<FileHeader>(&yaml).unwrap(), header);
+    }
+
+    #[test]
+    fn test_render_text_header() {
+        let config = FileLogConfig::new("logs/app.log");
+        let writer = HeaderWriter::new(
+            FileHeader::text("# pid {pid} {file} after {previous} {unknown}"),
+            &config,
+        );
+
+        let header = writer.render(Path::new("logs/app.log"), Some(Path::new("logs/app.log.1")));
+        assert_eq!(
+            String::from_utf8(header).unwrap(),
+            format!(
+                "# pid {} logs/app.log after logs/app.log.1 {{unknown}}\n",
+                std::process::id()
+            )
+        );
+    }
+
+    #[test]
+    fn test_render_json_header() {
+        let config = FileLogConfig::new("logs/app.log");
+        let writer = HeaderWriter::new(
+            FileHeader::json([("app", "myapp"), ("version", "1.2.3")]),
+            &config,
+        );
+
+        let header = writer.render(Path::new("logs/app.log"), None);
+        assert_eq!(header.last(), Some(&b'\n'));
+        let record: serde_json::Value = serde_json::from_slice(&header).unwrap();
+        assert_eq!(record["app"], "myapp");
+        assert_eq!(record["version"], "1.2.3");
+        assert_eq!(record["pid"], std::process::id());
+        assert_eq!(record["file"], "logs/app.log");
+        assert!(record["previous"].is_null());
+        assert_eq!(record["config"]["path"], "logs/app.log");
+    }
+
+    #[test]
+    fn test_find_header() {
+        let config = FileLogConfig::new("logs/app.log");
+        let writer =
+            HeaderWriter::new(FileHeader::text("# pid {pid}\n# after {previous}"), &config);
+
+        let header = writer.render(Path::new("logs/app.log"), Some(Path::new("logs/app.log.1")));
+        let file = [header.as_slice(), b"record\n"].concat();
+        assert_eq!(
+            writer.find_in(file.as_slice()).unwrap(),
+            Some(header.len() as u64)
+        );
+        // Written by another process, without a previous file
+        let file = b"# pid 1\n# after \nrecord\n";
+        assert_eq!(writer.find_in(&file[..]).unwrap(), Some(17));
+        for file in [
+            &b"record\n# pid 1\n"[..],
+            b"# pid 1\nrecord\n",
+            b"# pid 1\n",
+        ] {
+            assert_eq!(writer.find_in(file).unwrap(), None, "{:?}", file);
+        }
+
+        let writer = HeaderWriter::new(FileHeader::json([("app", "myapp")]), &config);
+        let header = writer.render(Path::new("logs/app.log"), None);
+        let file = [header.as_slice(), b"{\"msg\":\"record\"}\n"].concat();
+        assert_eq!(
+            writer.find_in(file.as_slice()).unwrap(),
+            Some(header.len() as u64)
+        );
+        let other = HeaderWriter::new(FileHeader::json([("app", "other")]), &config);
+        assert_eq!(other.find_in(file.as_slice()).unwrap(), None);
+        assert_eq!(
+            writer.find_in(&b"{\"msg\":\"record\"}\n"[..]).unwrap(),
+            None
+        );
+    }
+}
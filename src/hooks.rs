@@ -0,0 +1,130 @@
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread::JoinHandle;
+
+/// Why a log file was rotated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RotationReason {
+    /// The active file reached its maximum size.
+    Size,
+    /// The active file reached its maximum number of records.
+    Lines,
+    /// A new time period started.
+    Time,
+    /// Rotation was requested explicitly.
+    Manual,
+}
+
+/// Details of a rotation, passed to rotation hooks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RotationEvent {
+    /// Path of the closed file.
+    ///
+    /// With compression enabled, hooks run once the file has been compressed,
+    /// and this is the path of the compressed file.
+    pub closed: PathBuf,
+    /// Path of the new active file.
+    pub opened: PathBuf,
+    /// Why the file was rotated.
+    pub reason: RotationReason,
+    /// Size of the closed file in bytes, before compression.
+    pub bytes: u64,
+}
+
+/// A callback invoked after every rotation.
+pub(crate) type RotationHook = Arc<dyn Fn(&RotationEvent) + Send + Sync>;
+
+/// A list of rotation hooks that can be carried around by builders.
+#[derive(Clone, Default)]
+pub(crate) struct HookList(pub(crate) Vec<RotationHook>);
+
+impl fmt::Debug for HookList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{} rotation hook(s)]", self.0.len())
+    }
+}
+
+/// Runs rotation hooks on a background thread, so that slow hooks never
+/// stall logging.
+///
+/// The thread is only started once the first event is dispatched with hooks
+/// registered. Events are delivered in order; a panicking hook is reported
+/// and doesn't affect other hooks or later events.
+#[derive(Default)]
+pub(crate) struct RotationHooks {
+    hooks: Arc<RwLock<Vec<RotationHook>>>,
+    sender: Mutex<Option<Sender<RotationEvent>>>,
+    worker: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl RotationHooks {
+    /// Register a hook for all future events.
+    pub(crate) fn add(&self, hook: RotationHook) {
+        self.hooks.write().unwrap().push(hook);
+    }
+
+    /// Queue an event for the hooks.
+    pub(crate) fn dispatch(&self, event: RotationEvent) {
+        if self.hooks.read().unwrap().is_empty() {
+            return;
+        }
+
+        let mut sender = self.sender.lock().unwrap();
+        if sender.is_none() {
+            let (new_sender, receiver) = mpsc::channel::<RotationEvent>();
+            let hooks = Arc::clone(&self.hooks);
+            let worker = std::thread::Builder::new()
+                .name("lazylog-hooks".to_string())
+                .spawn(move || {
+                    for event in receiver {
+                        for hook in hooks.read().unwrap().iter() {
+                            let result =
+                                std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                                    hook(&event)
+                                }));
+                            if result.is_err() {
+                                eprintln!("lazylog: rotation hook panicked");
+                            }
+                        }
+                    }
+                });
+            match worker {
+                Ok(worker) => {
+                    *sender = Some(new_sender);
+                    *self.worker.lock().unwrap() = Some(worker);
+                }
+                Err(e) => {
+                    eprintln!("lazylog: failed to start rotation hook thread: {}", e);
+                    return;
+                }
+            }
+        }
+
+        if let Some(sender) = sender.as_ref() {
+            // The worker only exits once the sender is dropped
+            let _ = sender.send(event);
+        }
+    }
+}
+
+impl fmt::Debug for RotationHooks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "RotationHooks([{} hook(s)])",
+            self.hooks.read().unwrap().len()
+        )
+    }
+}
+
+impl Drop for RotationHooks {
+    fn drop(&mut self) {
+        // Closing the channel lets the worker run the queued events and exit
+        self.sender.get_mut().unwrap().take();
+        if let Some(worker) = self.worker.get_mut().unwrap().take() {
+            let _ = worker.join();
+        }
+    }
+}
@@ -49,21 +49,57 @@
 //! ```
 
 pub mod builder;
+/// Clocks for time-based rotation.
+pub mod clock;
+/// Background compression of rotated files.
+mod compress;
 /// Configuration structures for logging setup.
 pub mod config;
+/// Cron-style rotation schedules.
+pub mod cron;
+/// Durability modes for log files.
+pub mod durability;
 /// Error types for the logging library.
 pub mod error;
+/// Handling of failed writes to log files.
+pub mod failure;
+/// Handle for controlling logging after initialization.
+pub mod handle;
+/// Headers written at the start of new log files.
+pub mod header;
+/// Rotation lifecycle hooks.
+pub mod hooks;
+/// File naming templates for active and rotated files.
+pub mod naming;
+/// Permissions and ownership of log files.
+mod permissions;
 /// Log rotation functionality.
 pub mod rotation;
+/// Time zone settings.
+pub mod timezone;
 /// Tracing initialization utilities.
 pub mod tracing_init;
 /// Log writer implementations.
 pub mod writer;
 
 pub use builder::LogBuilder;
+#[cfg(feature = "testing")]
+pub use clock::ManualClock;
+pub use clock::{Clock, SystemClock};
 pub use config::{FileLogConfig, LogConfig};
+pub use cron::CronSchedule;
+pub use durability::Durability;
 pub use error::{Error, Result};
-pub use rotation::{RotationPeriod, RotationTrigger};
+pub use failure::WriteFailurePolicy;
+pub use handle::LoggingHandle;
+pub use header::FileHeader;
+pub use hooks::{RotationEvent, RotationReason};
+pub use naming::FileNaming;
+pub use rotation::{
+    Compression, OversizedRecord, PlainSizeUnit, Retention, RotationPeriod, RotationStrategy,
+    RotationTrigger, WeekSuffix, Weekday,
+};
+pub use timezone::Timezone;
 pub use tracing_init::init_logging;
 pub use writer::RotatingWriter;
 
@@ -0,0 +1,409 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, Result};
+
+/// Characters that separate placeholders in a naming template.
+const SEPARATORS: [char; 3] = ['.', '-', '_'];
+
+/// Placeholders in the rendered pattern used for matching file names.
+const DATE_MARKER: &str = "\u{0}";
+const INDEX_MARKER: &str = "\u{1}";
+
+/// A piece of a naming template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part {
+    Literal(String),
+    Name,
+    Stem,
+    Ext,
+    Date,
+    Index,
+}
+
+/// A piece of a file name pattern, after the base name has been filled in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Literal(String),
+    Date,
+    Index,
+}
+
+/// Template for the names of active and rotated log files.
+///
+/// Supported placeholders:
+/// - `{name}`: file name of the configured path (e.g. `app.log`)
+/// - `{stem}`: file name without its extension (e.g. `app`)
+/// - `{ext}`: extension of the file name (e.g. `log`)
+/// - `{date}`: time suffix of the period, for time-based rotation
+/// - `{index}`: position in the size rotation chain (`1` is the newest)
+///
+/// A placeholder without a value (e.g. `{index}` for the active file) is left
+/// out together with the separator (`.`, `-` or `_`) in front of it. The default
+/// template `{name}.{date}.{index}` produces `app.log`, `app.log.1`,
+/// `app.log.2026-01-09` and `app.log.2026-01-09.3`, while
+/// `{stem}-{date}.{index}.{ext}` keeps the extension last: `app-2026-01-09.3.log`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct FileNaming {
+    template: String,
+    parts: Vec<Part>,
+}
+
+impl FileNaming {
+    /// The default template, compatible with names used by earlier versions.
+    pub const DEFAULT_TEMPLATE: &'static str = "{name}.{date}.{index}";
+
+    /// Create a naming scheme from a template.
+    ///
+    /// The template must contain `{date}` and `{index}`, and either `{name}`
+    /// or `{stem}`.
+    pub fn new(template: impl Into<String>) -> Result<Self> {
+        let template = template.into();
+        let parts = parse_template(&template).map_err(Error::Config)?;
+
+        let has = |part: &Part| parts.contains(part);
+        if !has(&Part::Date) || !has(&Part::Index) {
+            return Err(Error::Config(format!(
+                "naming template `{}` must contain {{date}} and {{index}}",
+                template
+            )));
+        }
+        if !has(&Part::Name) && !has(&Part::Stem) {
+            return Err(Error::Config(format!(
+                "naming template `{}` must contain {{name}} or {{stem}}",
+                template
+            )));
+        }
+
+        Ok(Self { template, parts })
+    }
+
+    /// Get the template string.
+    pub fn template(&self) -> &str {
+        &self.template
+    }
+
+    /// Render the file name for `base_name` (the configured file name), a time
+    /// suffix (empty for none) and a rotation index.
+    pub fn file_name(&self, base_name: &str, date: &str, index: Option<usize>) -> String {
+        let index = index.map(|i| i.to_string()).unwrap_or_default();
+        self.render(base_name, date, &index)
+    }
+
+    /// Parse a file name produced by [`file_name`](Self::file_name) with a time
+    /// suffix, an index, or both.
+    ///
+    /// `is_date` decides which strings are valid time suffixes. Returns the time
+    /// suffix (empty for none) and the index, or `None` if the name doesn't match.
+    pub fn parse(
+        &self,
+        base_name: &str,
+        file_name: &str,
+        is_date: impl Fn(&str) -> bool,
+    ) -> Option<(String, Option<usize>)> {
+        // Try the most specific shapes first
+        [(true, true), (true, false), (false, true)]
+            .into_iter()
+            .find_map(|(with_date, with_index)| {
+                let pattern = self.render(
+                    base_name,
+                    if with_date { DATE_MARKER } else { "" },
+                    if with_index { INDEX_MARKER } else { "" },
+                );
+                match_segments(&segments(&pattern), file_name, &is_date)
+            })
+    }
+
+    /// Fill in the template, dropping empty placeholders with their separator.
+    fn render(&self, base_name: &str, date: &str, index: &str) -> String {
+        let (stem, ext) = split_extension(base_name);
+        let mut out = String::new();
+        // Set when an empty placeholder starts the name, so the separator after
+        // it is dropped instead
+        let mut skip_separator = false;
+
+        for part in &self.parts {
+            let value = match part {
+                Part::Literal(literal) => {
+                    let literal = if skip_separator {
+                        literal.strip_prefix(SEPARATORS).unwrap_or(literal)
+                    } else {
+                        literal
+                    };
+                    skip_separator = false;
+                    out.push_str(literal);
+                    continue;
+                }
+                Part::Name => base_name,
+                Part::Stem => stem,
+                Part::Ext => ext,
+                Part::Date => date,
+                Part::Index => index,
+            };
+
+            if value.is_empty() {
+                if out.ends_with(SEPARATORS) {
+                    out.pop();
+                } else if out.is_empty() {
+                    skip_separator = true;
+                }
+            } else {
+                out.push_str(value);
+            }
+        }
+
+        out
+    }
+}
+
+impl Default for FileNaming {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_TEMPLATE).expect("default template is valid")
+    }
+}
+
+impl TryFrom<String> for FileNaming {
+    type Error = String;
+
+    fn try_from(template: String) -> std::result::Result<Self, Self::Error> {
+        Self::new(template).map_err(|e| e.to_string())
+    }
+}
+
+impl From<FileNaming> for String {
+    fn from(naming: FileNaming) -> Self {
+        naming.template
+    }
+}
+
+/// Split a file name into stem and extension (`app.log` -> `app`, `log`).
+fn split_extension(name: &str) -> (&str, &str) {
+    match name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => (stem, ext),
+        _ => (name, ""),
+    }
+}
+
+/// Split a template into literals and placeholders.
+fn parse_template(template: &str) -> std::result::Result<Vec<Part>, String> {
+    let mut parts = Vec::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        if start > 0 {
+            parts.push(Part::Literal(rest[..start].to_string()));
+        }
+        let end = rest[start..]
+            .find('}')
+            .ok_or_else(|| format!("unclosed placeholder in naming template `{}`", template))?;
+        let part = match &rest[start + 1..start + end] {
+            "name" => Part::Name,
+            "stem" => Part::Stem,
+            "ext" => Part::Ext,
+            "date" => Part::Date,
+            "index" => Part::Index,
+            other => {
+                return Err(format!(
+                    "unknown placeholder {{{}}} in naming template `{}`, supported: name/stem/ext/date/index",
+                    other, template
+                ));
+            }
+        };
+        parts.push(part);
+        rest = &rest[start + end + 1..];
+    }
+    if !rest.is_empty() {
+        parts.push(Part::Literal(rest.to_string()));
+    }
+
+    if parts
+        .iter()
+        .any(|p| matches!(p, Part::Literal(l) if l.contains(['/', '\\'])))
+    {
+        return Err(format!(
+            "naming template `{}` must not contain path separators",
+            template
+        ));
+    }
+
+    Ok(parts)
+}
+
+/// Split a rendered pattern into literals and date/index markers.
+fn segments(pattern: &str) -> Vec<Segment> {
+    let mut segments = Vec::new();
+    let mut literal = String::new();
+
+    for c in pattern.chars() {
+        let marker = match c {
+            '\u{0}' => Segment::Date,
+            '\u{1}' => Segment::Index,
+            c => {
+                literal.push(c);
+                continue;
+            }
+        };
+        if !literal.is_empty() {
+            segments.push(Segment::Literal(std::mem::take(&mut literal)));
+        }
+        segments.push(marker);
+    }
+    if !literal.is_empty() {
+        segments.push(Segment::Literal(literal));
+    }
+
+    segments
+}
+
+/// Match a file name against pattern segments, backtracking over the length
+/// of the date and index.
+fn match_segments(
+    segments: &[Segment],
+    name: &str,
+    is_date: &dyn Fn(&str) -> bool,
+) -> Option<(String, Option<usize>)> {
+    let Some((segment, rest)) = segments.split_first() else {
+        return name.is_empty().then(|| (String::new(), None));
+    };
+
+    match segment {
+        Segment::Literal(literal) => {
+            match_segments(rest, name.strip_prefix(literal.as_str())?, is_date)
+        }
+        Segment::Date => (1..=name.len())
+            .filter(|&end| name.is_char_boundary(end) && is_date(&name[..end]))
+            .find_map(|end| {
+                let (_, index) = match_segments(rest, &name[end..], is_date)?;
+                Some((name[..end].to_string(), index))
+            }),
+        Segment::Index => {
+            let digits = name.bytes().take_while(u8::is_ascii_digit).count();
+            (1..=digits).find_map(|end| {
+                let index = name[..end].parse().ok()?;
+                let (date, _) = match_segments(rest, &name[end..], is_date)?;
+                Some((date, Some(index)))
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_date(s: &str) -> bool {
+        s.len() == 10 && s.bytes().filter(|b| *b == b'-').count() == 2
+    }
+
+    #[test]
+    fn test_default_naming() {
+        let naming = FileNaming::default();
+        assert_eq!(naming.file_name("app.log", "", None), "app.log");
+        assert_eq!(naming.file_name("app.log", "", Some(1)), "app.log.1");
+        assert_eq!(
+            naming.file_name("app.log", "2026-01-09", None),
+            "app.log.2026-01-09"
+        );
+        assert_eq!(
+            naming.file_name("app.log", "2026-01-09", Some(3)),
+            "app.log.2026-01-09.3"
+        );
+    }
+
+    #[test]
+    fn test_extension_last_naming() {
+        let naming = FileNaming::new("{stem}-{date}.{index}.{ext}").unwrap();
+        assert_eq!(naming.file_name("app.log", "", None), "app.log");
+        assert_eq!(naming.file_name("app.log", "", Some(2)), "app.2.log");
+        assert_eq!(
+            naming.file_name("app.log", "2026-01-09", None),
+            "app-2026-01-09.log"
+        );
+        assert_eq!(
+            naming.file_name("app.log", "2026-01-09", Some(3)),
+            "app-2026-01-09.3.log"
+        );
+        // No extension to keep
+        assert_eq!(
+            naming.file_name("app", "2026-01-09", Some(3)),
+            "app-2026-01-09.3"
+        );
+    }
+
+    #[test]
+    fn test_date_first_naming() {
+        let naming = FileNaming::new("{date}_{index}_{name}").unwrap();
+        assert_eq!(naming.file_name("app.log", "", None), "app.log");
+        assert_eq!(naming.file_name("app.log", "", Some(1)), "1_app.log");
+        assert_eq!(
+            naming.file_name("app.log", "2026-01-09", Some(1)),
+            "2026-01-09_1_app.log"
+        );
+        assert_eq!(
+            naming.file_name("app.log", "2026-01-09", None),
+            "2026-01-09_app.log"
+        );
+    }
+
+    #[test]
+    fn test_parse_roundtrip() {
+        for template in [
+            FileNaming::DEFAULT_TEMPLATE,
+            "{stem}-{date}.{index}.{ext}",
+            "{stem}.{index}.{date}.{ext}",
+            "{date}_{index}_{name}",
+        ] {
+            let naming = FileNaming::new(template).unwrap();
+            for (date, index) in [
+                ("2026-01-09", Some(12)),
+                ("2026-01-09", None),
+                ("", Some(3)),
+            ] {
+                let name = naming.file_name("app.log", date, index);
+                assert_eq!(
+                    naming.parse("app.log", &name, is_date),
+                    Some((date.to_string(), index)),
+                    "template {} name {}",
+                    template,
+                    name
+                );
+            }
+            // The base name itself is not a rotated file
+            assert_eq!(naming.parse("app.log", "app.log", is_date), None);
+        }
+    }
+
+    #[test]
+    fn test_parse_rejects_other_files() {
+        let naming = FileNaming::new("{stem}-{date}.{index}.{ext}").unwrap();
+        assert_eq!(
+            naming.parse("app.log", "app-2026-01-09.x.log", is_date),
+            None
+        );
+        assert_eq!(
+            naming.parse("app.log", "other-2026-01-09.log", is_date),
+            None
+        );
+        assert_eq!(naming.parse("app.log", "app-notadate.log", is_date), None);
+        assert_eq!(naming.parse("app.log", "app.log.1", is_date), None);
+    }
+
+    #[test]
+    fn test_invalid_templates() {
+        assert!(FileNaming::new("{name}.{index}").is_err());
+        assert!(FileNaming::new("{name}.{date}").is_err());
+        assert!(FileNaming::new("{ext}.{date}.{index}").is_err());
+        assert!(FileNaming::new("{name}.{date}.{index}.{bogus}").is_err());
+        assert!(FileNaming::new("{name}.{date}.{index").is_err());
+        assert!(FileNaming::new("logs/{name}.{date}.{index}").is_err());
+    }
+
+    #[test]
+    fn test_naming_serde() {
+        let naming: FileNaming = serde_yaml::from_str("'{stem}-{date}.{index}.{ext}'").unwrap();
+        assert_eq!(naming.template(), "{stem}-{date}.{index}.{ext}");
+        let yaml = serde_yaml::to_string(&naming).unwrap();
+        assert_eq!(serde_yaml::from_str::<FileNaming>(&yaml).unwrap(), naming);
+        assert!(serde_yaml::from_str::<FileNaming>("'{name}'").is_err());
+    }
+}
@@ -0,0 +1,185 @@
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::path::Path;
+
+use crate::FileLogConfig;
+
+/// Permissions and ownership applied to log files and the directories
+/// created for them. Only supported on Unix; ignored elsewhere.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct Permissions {
+    file_mode: Option<u32>,
+    dir_mode: Option<u32>,
+    owner: Option<u32>,
+    group: Option<u32>,
+}
+
+impl Permissions {
+    pub(crate) fn from_config(config: &FileLogConfig) -> Self {
+        Self {
+            file_mode: config.file_mode,
+            dir_mode: config.dir_mode,
+            owner: config.owner,
+            group: config.group,
+        }
+    }
+
+    /// Options for opening log files, creating them with the file mode.
+    pub(crate) fn open_options(&self) -> OpenOptions {
+        #[allow(unused_mut)]
+        let mut options = OpenOptions::new();
+        #[cfg(unix)]
+        if let Some(mode) = self.file_mode {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(mode);
+        }
+        options
+    }
+
+    /// Apply the file mode and ownership to an open log file.
+    #[cfg(unix)]
+    pub(crate) fn apply_to_file(&self, file: &File) -> io::Result<()> {
+        use std::os::unix::fs::{MetadataExt, PermissionsExt};
+
+        let metadata = file.metadata()?;
+        if let Some(mode) = self.file_mode
+            && metadata.mode() & 0o7777 != mode
+        {
+            file.set_permissions(std::fs::Permissions::from_mode(mode))?;
+        }
+        // Only change what differs, which doesn't need privileges
+        if self.owner.is_some_and(|owner| owner != metadata.uid())
+            || self.group.is_some_and(|group| group != metadata.gid())
+        {
+            std::os::unix::fs::fchown(file, self.owner, self.group)?;
+        }
+        Ok(())
+    }
+
+    /// Apply the file mode and ownership to an open log file.
+    #[cfg(not(unix))]
+    pub(crate) fn apply_to_file(&self, _file: &File) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Apply the file mode and ownership to the log file at `path`.
+    pub(crate) fn apply_to_path(&self, path: &Path) -> io::Result<()> {
+        if *self == Self::default() {
+            return Ok(());
+        }
+        self.apply_to_file(&File::open(path)?)
+    }
+
+    /// Create a directory and its missing parents. Directories created here
+    /// get the directory mode and ownership; existing ones are left alone.
+    pub(crate) fn create_dir_all(&self, dir: &Path) -> io::Result<()> {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+
+            let missing: Vec<&Path> = dir
+                .ancestors()
+                .take_while(|ancestor| !ancestor.as_os_str().is_empty() && !ancestor.exists())
+                .collect();
+            std::fs::create_dir_all(dir)?;
+
+            for created in missing.iter().rev() {
+                if let Some(mode) = self.dir_mode {
+                    std::fs::set_permissions(created, std::fs::Permissions::from_mode(mode))?;
+                }
+                if self.owner.is_some() || self.group.is_some() {
+                    std::os::unix::fs::chown(created, self.owner, self.group)?;
+                }
+            }
+            Ok(())
+        }
+        #[cfg(not(unix))]
+        {
+            std::fs::create_dir_all(dir)
+        }
+    }
+}
+
+/// Serde helpers for optional Unix modes, written as octal strings (e.g. "0640").
+///
+/// A number is read as if its digits were octal, so `640` is `0o640`.
+pub(crate) mod opt_mode {
+    use serde::{Deserialize, Deserializer, Serializer, de};
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum ModeValue {
+        Number(u64),
+        String(String),
+    }
+
+    /// Parse an octal mode such as `0640`, `640` or `0o640`.
+    pub(crate) fn parse_mode(s: &str) -> Result<u32, String> {
+        let s = s.trim();
+        let digits = s.strip_prefix("0o").unwrap_or(s);
+        u32::from_str_radix(digits, 8)
+            .ok()
+            .filter(|mode| *mode <= 0o7777)
+            .ok_or_else(|| format!("invalid mode `{}`: expected octal digits like 0640", s))
+    }
+
+    pub fn serialize<S>(value: &Option<u32>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match value {
+            Some(mode) => serializer.serialize_some(&format!("{:04o}", mode)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<u32>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Option::<ModeValue>::deserialize(deserializer)?
+            .map(|value| match value {
+                ModeValue::Number(n) => parse_mode(&n.to_string()),
+                ModeValue::String(s) => parse_mode(&s),
+            })
+            .transpose()
+            .map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_mode() {
+        assert_eq!(opt_mode::parse_mode("0640"), Ok(0o640));
+        assert_eq!(opt_mode::parse_mode("750"), Ok(0o750));
+        assert_eq!(opt_mode::parse_mode("0o600"), Ok(0o600));
+        assert_eq!(opt_mode::parse_mode("2775"), Ok(0o2775));
+
+        for invalid in ["", "0689", "rw-r-----", "17777"] {
+            assert!(opt_mode::parse_mode(invalid).is_err(), "{:?}", invalid);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_create_dir_all_sets_mode_on_created_dirs() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let root = tempfile::tempdir().unwrap();
+        let permissions = Permissions {
+            dir_mode: Some(0o750),
+            ..Default::default()
+        };
+        let dir = root.path().join("a").join("b");
+        permissions.create_dir_all(&dir).unwrap();
+
+        let mode = |path: &Path| std::fs::metadata(path).unwrap().permissions().mode() & 0o7777;
+        assert_eq!(mode(&root.path().join("a")), 0o750);
+        assert_eq!(mode(&dir), 0o750);
+        // Existing directories are left alone
+        assert_ne!(mode(root.path()), 0o750);
+    }
+}
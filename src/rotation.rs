@@ -1,36 +1,100 @@
-use serde::{Deserialize, Deserializer, Serialize, de};
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
 #[cfg(feature = "time")]
 use time::OffsetDateTime;
 
-/// Parse a size string with optional units (K/M/G, case-insensitive), defaulting to KB if no unit.
-fn parse_size(s: &str) -> Result<u64, String> {
-    let s = s.trim();
-    if s.is_empty() {
-        return Err("empty size string".to_string());
+use crate::CronSchedule;
+use crate::clock::{Clock, SystemClock};
+
+/// What a size without a unit means in config.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlainSizeUnit {
+    /// Kilobytes, so `max_size: 10240` is 10M (compatible with earlier versions).
+    #[default]
+    Kilobytes,
+    /// Bytes, so `max_size: 1048576` is 1M.
+    Bytes,
+}
+
+/// Split a number with an optional unit, e.g. `1.5 G`, into its integer
+/// digits, fraction digits and unit.
+fn split_quantity(s: &str) -> Option<(&str, &str, &str)> {
+    let number_len = s
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(s.len());
+    let (number, unit) = s.split_at(number_len);
+    let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+    if whole.is_empty() || (number.contains('.') && fraction.is_empty()) || fraction.contains('.') {
+        return None;
     }
+    Some((whole, fraction, unit.trim_start()))
+}
 
-    let (num_str, unit) = if s.chars().last().unwrap().is_alphabetic() {
-        let len = s.len();
-        let num_part = &s[..len - 1];
-        let unit_char = s.chars().last().unwrap().to_ascii_uppercase();
-        (num_part, unit_char)
-    } else {
-        (s, 'K') // Default to KB
-    };
+/// Why a decimal number couldn't be scaled to a whole number of units.
+enum ScaleError {
+    TooLarge,
+    Fractional,
+}
+
+/// Multiply a decimal number by `multiplier`, if the result is a whole number.
+fn scale_decimal(whole: &str, fraction: &str, multiplier: u64) -> Result<u64, ScaleError> {
+    let digits = format!("{}{}", whole, fraction);
+    let scaled = digits
+        .parse::<u128>()
+        .ok()
+        .and_then(|number| number.checked_mul(u128::from(multiplier)))
+        .ok_or(ScaleError::TooLarge)?;
+    let divisor = 10u128
+        .checked_pow(fraction.len() as u32)
+        .ok_or(ScaleError::Fractional)?;
+    if !scaled.is_multiple_of(divisor) {
+        return Err(ScaleError::Fractional);
+    }
+    u64::try_from(scaled / divisor).map_err(|_| ScaleError::TooLarge)
+}
 
-    let num: u64 = num_str
-        .parse()
-        .map_err(|_| format!("invalid number: {}", num_str))?;
+/// Parse a size such as `64K`, `1.5G` or `512 MiB` into bytes.
+///
+/// Units are case-insensitive and binary: `K`, `KB` and `KiB` all mean 1024
+/// bytes, likewise for `M`, `G` and `T`; `B` means bytes. A number without a
+/// unit is read according to `plain`, and must be an integer.
+fn parse_size_with(s: &str, plain: PlainSizeUnit) -> Result<u64, String> {
+    let s = s.trim();
+    let invalid = |reason: &str| {
+        format!(
+            "invalid size `{}`: {}; expected a number with a unit (B, K/KB/KiB, M/MB/MiB, \
+             G/GB/GiB, T/TB/TiB), e.g. 512K or 1.5G",
+            s, reason
+        )
+    };
+    if s.is_empty() {
+        return Err(invalid("empty"));
+    }
 
-    let multiplier = match unit {
-        'K' => 1024,
-        'M' => 1024 * 1024,
-        'G' => 1024 * 1024 * 1024,
-        _ => return Err(format!("invalid unit: {}, supported: K/M/G", unit)),
+    let (whole, fraction, unit) = split_quantity(s).ok_or_else(|| invalid("not a number"))?;
+    let exponent = match unit.to_ascii_lowercase().as_str() {
+        "" if !fraction.is_empty() => {
+            return Err(invalid("a decimal number needs a unit"));
+        }
+        "" => match plain {
+            PlainSizeUnit::Bytes => 0,
+            PlainSizeUnit::Kilobytes => 1,
+        },
+        "b" => 0,
+        "k" | "kb" | "kib" => 1,
+        "m" | "mb" | "mib" => 2,
+        "g" | "gb" | "gib" => 3,
+        "t" | "tb" | "tib" => 4,
+        _ => return Err(invalid(&format!("unknown unit `{}`", unit))),
     };
 
-    num.checked_mul(multiplier)
-        .ok_or_else(|| "size too large".to_string())
+    scale_decimal(whole, fraction, 1024u64.pow(exponent)).map_err(|e| match e {
+        ScaleError::TooLarge => invalid("too large"),
+        ScaleError::Fractional => invalid("not a whole number of bytes"),
+    })
 }
 
 /// Size value that can be a number or string with units.
@@ -38,21 +102,225 @@ fn parse_size(s: &str) -> Result<u64, String> {
 #[serde(untagged)]
 enum SizeValue {
     Number(u64),
+    Float(f64),
     String(String),
 }
 
 impl SizeValue {
-    fn to_bytes(&self) -> Result<u64, String> {
+    /// Get the size in bytes, reading a number without a unit as `plain`.
+    fn to_bytes(&self, plain: PlainSizeUnit) -> Result<u64, String> {
         match self {
-            SizeValue::Number(n) => parse_size(&n.to_string()),
-            SizeValue::String(s) => parse_size(s),
+            SizeValue::Number(n) => parse_size_with(&n.to_string(), plain),
+            SizeValue::Float(n) => parse_size_with(&n.to_string(), plain),
+            SizeValue::String(s) => parse_size_with(s, plain),
         }
     }
 }
 
+/// Rewrite a size in config with an explicit unit, reading a number without
+/// one as `plain`, so that it reads the same whatever the plain unit.
+pub(crate) fn normalize_size(
+    value: &mut serde_json::Value,
+    plain: PlainSizeUnit,
+) -> Result<(), String> {
+    if value.is_null() {
+        return Ok(());
+    }
+    let bytes = SizeValue::deserialize(value.take())
+        .map_err(|e| e.to_string())?
+        .to_bytes(plain)?;
+    *value = format_size(bytes).into();
+    Ok(())
+}
+
+/// Format a size using the largest unit (K/M/G/T) that represents it
+/// exactly, or in bytes.
+fn format_size(size: u64) -> String {
+    for (unit, exponent) in [('T', 4), ('G', 3), ('M', 2), ('K', 1)] {
+        let multiplier = 1024u64.pow(exponent);
+        if size != 0 && size.is_multiple_of(multiplier) {
+            return format!("{}{}", size / multiplier, unit);
+        }
+    }
+    format!("{}B", size)
+}
+
+/// Serde helpers for sizes written as strings with units (e.g. "64K").
+pub(crate) mod size {
+    use super::*;
+
+    pub fn serialize<S>(value: &u64, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&format_size(*value))
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<u64, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        SizeValue::deserialize(deserializer)?
+            .to_bytes(PlainSizeUnit::default())
+            .map_err(de::Error::custom)
+    }
+}
+
+/// Serde helpers for optional sizes written as strings with units (e.g. "2G").
+pub(crate) mod opt_size {
+    use super::*;
+
+    pub fn serialize<S>(value: &Option<u64>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match value {
+            Some(size) => serializer.serialize_some(&format_size(*size)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<u64>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Option::<SizeValue>::deserialize(deserializer)?
+            .map(|value| {
+                value
+                    .to_bytes(PlainSizeUnit::default())
+                    .map_err(de::Error::custom)
+            })
+            .transpose()
+    }
+}
+
+/// Parse a duration such as `30s`, `15m`, `1.5h` or `7 days`.
+///
+/// Units are case-insensitive: `ms`, `s`, `m`, `h`, `d` and `w`, or their
+/// names (`sec`, `minutes`, `hours`, ...). A number without a unit is in
+/// seconds. Durations are limited to whole milliseconds.
+pub(crate) fn parse_duration(s: &str) -> Result<Duration, String> {
+    let s = s.trim();
+    let invalid = |reason: &str| {
+        format!(
+            "invalid duration `{}`: {}; expected a number with a unit (ms, s, m, h, d, w), \
+             e.g. 30s, 15m or 7d",
+            s, reason
+        )
+    };
+    if s.is_empty() {
+        return Err(invalid("empty"));
+    }
+
+    let (whole, fraction, unit) = split_quantity(s).ok_or_else(|| invalid("not a number"))?;
+    let millis: u64 = match unit.to_ascii_lowercase().as_str() {
+        "ms" | "msec" | "millis" | "millisecond" | "milliseconds" => 1,
+        "" | "s" | "sec" | "secs" | "second" | "seconds" => 1000,
+        "m" | "min" | "mins" | "minute" | "minutes" => 60 * 1000,
+        "h" | "hr" | "hrs" | "hour" | "hours" => 60 * 60 * 1000,
+        "d" | "day" | "days" => 24 * 60 * 60 * 1000,
+        "w" | "week" | "weeks" => 7 * 24 * 60 * 60 * 1000,
+        "mo" | "month" | "months" | "y" | "year" | "years" => {
+            return Err(invalid(
+                "months and years vary in length, use days or a monthly rotation period",
+            ));
+        }
+        _ => return Err(invalid(&format!("unknown unit `{}`", unit))),
+    };
+
+    scale_decimal(whole, fraction, millis)
+        .map(Duration::from_millis)
+        .map_err(|e| match e {
+            ScaleError::TooLarge => invalid("too large"),
+            ScaleError::Fractional => invalid("not a whole number of milliseconds"),
+        })
+}
+
+/// Format a duration using the largest unit that represents it exactly.
+pub(crate) fn format_duration(duration: Duration) -> String {
+    if duration.subsec_nanos() != 0 {
+        return format!("{}ms", duration.as_millis());
+    }
+    let secs = duration.as_secs();
+    for (unit, multiplier) in [
+        ('w', 7 * 24 * 60 * 60),
+        ('d', 24 * 60 * 60),
+        ('h', 60 * 60),
+        ('m', 60),
+    ] {
+        if secs != 0 && secs.is_multiple_of(multiplier) {
+            return format!("{}{}", secs / multiplier, unit);
+        }
+    }
+    format!("{}s", secs)
+}
+
+/// Duration value that can be a number of seconds or string with units.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum DurationValue {
+    Number(u64),
+    Float(f64),
+    String(String),
+}
+
+impl DurationValue {
+    fn to_duration(&self) -> Result<Duration, String> {
+        match self {
+            DurationValue::Number(n) => Ok(Duration::from_secs(*n)),
+            DurationValue::Float(n) => parse_duration(&n.to_string()),
+            DurationValue::String(s) => parse_duration(s),
+        }
+    }
+}
+
+/// Serde helpers for optional durations written as strings with units (e.g. "7d").
+mod opt_duration {
+    use super::*;
+
+    pub fn serialize<S>(value: &Option<Duration>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match value {
+            Some(duration) => serializer.serialize_some(&format_duration(*duration)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<Duration>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Option::<DurationValue>::deserialize(deserializer)?
+            .map(|value| value.to_duration().map_err(de::Error::custom))
+            .transpose()
+    }
+}
+
+/// Serde helpers for durations written as strings with units (e.g. "1s").
+pub(crate) mod duration {
+    use super::*;
+
+    pub fn serialize<S>(value: &Duration, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&format_duration(*value))
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Duration, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        DurationValue::deserialize(deserializer)?
+            .to_duration()
+            .map_err(de::Error::custom)
+    }
+}
+
 /// Rotation trigger for log files.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Default)]
-#[serde(rename_all = "snake_case")]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub enum RotationTrigger {
     /// Never rotate.
     #[default]
@@ -65,135 +333,281 @@ pub enum RotationTrigger {
     /// Rotate based on file size.
     Size {
         /// Maximum file size in bytes before rotation.
-        /// Can be specified as a number (defaults to KB) or string with units (K/M/G, case-insensitive).
-        /// Examples: 10 (10KB), "5M" (5MB), "1G" (1GB), "2k" (2KB), "3m" (3MB), "4g" (4GB)
+        /// Can be specified as a string with a binary unit (B, K/KB/KiB, M/MB/MiB,
+        /// G/GB/GiB, T/TB/TiB, case-insensitive; KB and MB mean 1024 and 1024² bytes),
+        /// possibly decimal, or as an integer in KB unless `plain_sizes` is `bytes`.
+        /// Examples: 10 (10 KiB), "512B", "5M" (5 MiB), "1.5G", "2 KiB"
         max_size: u64,
         /// Maximum number of files to keep.
         max_files: usize,
     },
+    /// Rotate based on the number of records (newline-terminated lines).
+    Lines {
+        /// Maximum number of records per file.
+        max_lines: u64,
+        /// Maximum number of files to keep.
+        max_files: usize,
+    },
     /// Rotate based on both time and size.
     Both {
         /// The time period for rotation.
         period: RotationPeriod,
         /// Maximum file size in bytes before rotation.
-        /// Can be specified as a number (defaults to KB) or string with units (K/M/G, case-insensitive).
-        /// Examples: 10 (10KB), "5M" (5MB), "1G" (1GB), "2k" (2KB), "3m" (3MB), "4g" (4GB)
+        /// Can be specified as a string with a binary unit (B, K/KB/KiB, M/MB/MiB,
+        /// G/GB/GiB, T/TB/TiB, case-insensitive; KB and MB mean 1024 and 1024² bytes),
+        /// possibly decimal, or as an integer in KB unless `plain_sizes` is `bytes`.
+        /// Examples: 10 (10 KiB), "512B", "5M" (5 MiB), "1.5G", "2 KiB"
         max_size: u64,
+        /// Maximum number of records per file, if limited.
+        max_lines: Option<u64>,
         /// Maximum number of files to keep.
         max_files: usize,
     },
 }
 
-impl<'de> Deserialize<'de> for RotationTrigger {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        #[derive(Deserialize)]
-        #[serde(untagged)]
-        enum RotationInput {
-            Simple(String),
-            Complex {
-                #[serde(rename = "type")]
-                rotation_type: Option<String>,
-                period: Option<RotationPeriod>,
-                max_size: Option<SizeValue>,
-                max_files: Option<usize>,
-            },
-        }
+/// Fields of a rotation trigger in config.
+#[derive(Deserialize, Default)]
+struct RotationFields {
+    #[serde(rename = "type")]
+    rotation_type: Option<String>,
+    period: Option<RotationPeriod>,
+    max_size: Option<SizeValue>,
+    max_lines: Option<u64>,
+    #[serde(alias = "max_backups")]
+    max_files: Option<usize>,
+}
 
-        let input = RotationInput::deserialize(deserializer)?;
-
-        match input {
-            RotationInput::Simple(rotation_type) => match rotation_type.as_str() {
-                "never" => Ok(RotationTrigger::Never),
-                "size" => Ok(RotationTrigger::Size {
-                    max_size: 10 * 1024 * 1024,
-                    max_files: 5,
-                }),
-                "time" => {
-                    #[cfg(feature = "time")]
-                    {
-                        Ok(RotationTrigger::Time {
-                            period: RotationPeriod::Daily,
-                        })
-                    }
-                    #[cfg(not(feature = "time"))]
-                    {
-                        Err(de::Error::custom(
-                            "time-based rotation requires time feature",
-                        ))
-                    }
+const ROTATION_FIELDS: &[&str] = &[
+    "type",
+    "period",
+    "max_size",
+    "max_lines",
+    "max_files",
+    "max_backups",
+];
+
+/// Parse a rotation trigger in any of the config shapes:
+///
+/// - a name: `never`, `size`, `lines`, `time` or `both`, with defaults
+/// - fields with a `type`: `{ type: size, max_size: 10M, max_files: 5 }`
+/// - fields under the type: `{ size: { max_size: 10M, max_backups: 5 } }`,
+///   or its main setting alone: `{ size: 10M }`
+/// - fields under a period: `{ daily: { max_backups: 7 } }`
+///
+/// Sizes without a unit are read as `plain`. Time-based triggers don't keep
+/// a number of files themselves; their `max_files` is returned alongside, for
+/// the retention policy.
+pub(crate) fn parse_rotation(
+    value: serde_json::Value,
+    plain: PlainSizeUnit,
+) -> Result<(RotationTrigger, Option<usize>), String> {
+    use serde_json::Value;
+
+    let fields_from = |value: Value| RotationFields::deserialize(value).map_err(|e| e.to_string());
+
+    let fields = match value {
+        Value::String(rotation_type) => {
+            return parse_rotation_name(&rotation_type).map(|t| (t, None));
+        }
+        Value::Object(map)
+            if map.len() == 1
+                && !map
+                    .keys()
+                    .any(|key| ROTATION_FIELDS.contains(&key.as_str())) =>
+        {
+            let (key, inner) = map.into_iter().next().unwrap();
+            let mut fields = match inner {
+                Value::Null => RotationFields::default(),
+                Value::Object(_) => fields_from(inner)?,
+                setting => {
+                    let main_field = match key.as_str() {
+                        "size" => "max_size",
+                        "lines" => "max_lines",
+                        "time" | "both" => "period",
+                        _ => return Err(format!("rotation `{}` expects a map of settings", key)),
+                    };
+                    fields_from(serde_json::json!({ main_field: setting }))?
                 }
-                "both" => {
-                    #[cfg(feature = "time")]
-                    {
-                        Ok(RotationTrigger::Both {
-                            period: RotationPeriod::Daily,
-                            max_size: 10 * 1024 * 1024,
-                            max_files: 5,
-                        })
-                    }
-                    #[cfg(not(feature = "time"))]
-                    {
-                        Err(de::Error::custom(
-                            "time-based rotation requires time feature",
-                        ))
-                    }
+            };
+            if fields.rotation_type.is_some() {
+                return Err(format!("rotation `{}` can't also have a type", key));
+            }
+            if ["never", "time", "size", "lines", "both"].contains(&key.as_str()) {
+                fields.rotation_type = Some(key);
+            } else {
+                let period = key
+                    .parse::<RotationPeriod>()
+                    .map_err(|_| format!("unknown rotation type: {}", key))?;
+                if fields.period.is_some() {
+                    return Err(format!("rotation `{}` can't also have a period", key));
                 }
-                other => Err(de::Error::custom(format!(
-                    "unknown rotation type: {}",
-                    other
-                ))),
+                fields.period = Some(period);
+                fields.rotation_type = Some(
+                    if fields.max_size.is_some() || fields.max_lines.is_some() {
+                        "both"
+                    } else {
+                        "time"
+                    }
+                    .to_string(),
+                );
+            }
+            fields
+        }
+        value => fields_from(value)?,
+    };
+
+    let max_size = |required_for: &str| {
+        fields
+            .max_size
+            .as_ref()
+            .ok_or_else(|| format!("max_size is required for {} rotation", required_for))?
+            .to_bytes(plain)
+    };
+    let max_files = fields.max_files.unwrap_or(5);
+    if fields.max_lines == Some(0) {
+        return Err("max_lines must be greater than 0".to_string());
+    }
+
+    match fields.rotation_type.as_deref() {
+        Some("never") | None => Ok((RotationTrigger::Never, None)),
+        Some("time") => {
+            let period = fields
+                .period
+                .ok_or("period is required for time-based rotation")?;
+            if fields.max_size.is_some() || fields.max_lines.is_some() {
+                return Err(
+                    "max_size and max_lines need time+size rotation (type: both)".to_string(),
+                );
+            }
+            Ok((RotationTrigger::Time { period }, fields.max_files))
+        }
+        Some("size") => Ok((
+            RotationTrigger::Size {
+                max_size: max_size("size-based")?,
+                max_files,
             },
-            RotationInput::Complex {
-                rotation_type,
+            None,
+        )),
+        Some("lines") => {
+            let max_lines = fields
+                .max_lines
+                .ok_or("max_lines is required for line-based rotation")?;
+            Ok((
+                RotationTrigger::Lines {
+                    max_lines,
+                    max_files,
+                },
+                None,
+            ))
+        }
+        Some("both") => {
+            let period = fields
+                .period
+                .ok_or("period is required for time+size rotation")?;
+            Ok((
+                RotationTrigger::Both {
+                    period,
+                    max_size: max_size("time+size")?,
+                    max_lines: fields.max_lines,
+                    max_files,
+                },
+                None,
+            ))
+        }
+        Some(other) => Err(format!("unknown rotation type: {}", other)),
+    }
+}
+
+/// Parse a rotation trigger given by name, with default settings.
+fn parse_rotation_name(rotation_type: &str) -> Result<RotationTrigger, String> {
+    match rotation_type {
+        "never" => Ok(RotationTrigger::Never),
+        "size" => Ok(RotationTrigger::Size {
+            max_size: 10 * 1024 * 1024,
+            max_files: 5,
+        }),
+        "lines" => Ok(RotationTrigger::Lines {
+            max_lines: 100_000,
+            max_files: 5,
+        }),
+        #[cfg(feature = "time")]
+        "time" => Ok(RotationTrigger::Time {
+            period: RotationPeriod::Daily,
+        }),
+        #[cfg(feature = "time")]
+        "both" => Ok(RotationTrigger::Both {
+            period: RotationPeriod::Daily,
+            max_size: 10 * 1024 * 1024,
+            max_lines: None,
+            max_files: 5,
+        }),
+        #[cfg(not(feature = "time"))]
+        "time" | "both" => Err("time-based rotation requires time feature".to_string()),
+        other => Err(format!("unknown rotation type: {}", other)),
+    }
+}
+
+impl Serialize for RotationTrigger {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        use serde::ser::SerializeMap;
+
+        let mut map = match self {
+            Self::Never => return serializer.serialize_str("never"),
+            Self::Time { period } => {
+                let mut map = serializer.serialize_map(Some(2))?;
+                map.serialize_entry("type", "time")?;
+                map.serialize_entry("period", period)?;
+                return map.end();
+            }
+            Self::Size { max_size, .. } => {
+                let mut map = serializer.serialize_map(Some(3))?;
+                map.serialize_entry("type", "size")?;
+                map.serialize_entry("max_size", &format_size(*max_size))?;
+                map
+            }
+            Self::Lines { max_lines, .. } => {
+                let mut map = serializer.serialize_map(Some(3))?;
+                map.serialize_entry("type", "lines")?;
+                map.serialize_entry("max_lines", max_lines)?;
+                map
+            }
+            Self::Both {
                 period,
                 max_size,
-                max_files,
-            } => match rotation_type.as_deref() {
-                Some("never") | None => Ok(RotationTrigger::Never),
-                Some("time") => {
-                    let period = period.ok_or_else(|| {
-                        de::Error::custom("period is required for time-based rotation")
-                    })?;
-                    Ok(RotationTrigger::Time { period })
-                }
-                Some("size") => {
-                    let max_size = max_size
-                        .ok_or_else(|| {
-                            de::Error::custom("max_size is required for size-based rotation")
-                        })?
-                        .to_bytes()
-                        .map_err(de::Error::custom)?;
-                    let max_files = max_files.unwrap_or(5);
-                    Ok(RotationTrigger::Size {
-                        max_size,
-                        max_files,
-                    })
+                max_lines,
+                ..
+            } => {
+                let mut map = serializer.serialize_map(None)?;
+                map.serialize_entry("type", "both")?;
+                map.serialize_entry("period", period)?;
+                map.serialize_entry("max_size", &format_size(*max_size))?;
+                if let Some(max_lines) = max_lines {
+                    map.serialize_entry("max_lines", max_lines)?;
                 }
-                Some("both") => {
-                    let period = period.ok_or_else(|| {
-                        de::Error::custom("period is required for time+size rotation")
-                    })?;
-                    let max_size = max_size
-                        .ok_or_else(|| {
-                            de::Error::custom("max_size is required for time+size rotation")
-                        })?
-                        .to_bytes()
-                        .map_err(de::Error::custom)?;
-                    let max_files = max_files.unwrap_or(5);
-                    Ok(RotationTrigger::Both {
-                        period,
-                        max_size,
-                        max_files,
-                    })
-                }
-                Some(other) => Err(de::Error::custom(format!(
-                    "unknown rotation type: {}",
-                    other
-                ))),
-            },
+                map
+            }
+        };
+        map.serialize_entry("max_files", &self.max_files())?;
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for RotationTrigger {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        match parse_rotation(value, PlainSizeUnit::default()).map_err(de::Error::custom)? {
+            (trigger, None) => Ok(trigger),
+            // Only a file config can move them into its retention policy
+            (_, Some(_)) => Err(de::Error::custom(
+                "time-based rotation doesn't keep a number of files; \
+                 set retention.max_periods instead of max_backups",
+            )),
         }
     }
 }
@@ -207,6 +621,14 @@ impl RotationTrigger {
         }
     }
 
+    /// Create a rotation trigger based on the number of records.
+    pub fn lines(max_lines: u64, max_files: usize) -> Self {
+        Self::Lines {
+            max_lines,
+            max_files,
+        }
+    }
+
     /// Create a time-based rotation trigger.
     #[cfg(feature = "time")]
     pub fn time(period: RotationPeriod) -> Self {
@@ -219,73 +641,668 @@ impl RotationTrigger {
         Self::Both {
             period,
             max_size,
+            max_lines: None,
             max_files,
         }
     }
 
-    /// Get the maximum number of files to keep.
-    pub fn max_files(&self) -> Option<usize> {
+    /// Also rotate a hybrid trigger after `max_lines` records.
+    ///
+    /// Has no effect on other triggers.
+    pub fn with_max_lines(mut self, max_lines: u64) -> Self {
+        if let Self::Both {
+            max_lines: limit, ..
+        } = &mut self
+        {
+            *limit = Some(max_lines);
+        }
+        self
+    }
+
+    /// Get the maximum file size for size-based and hybrid rotation.
+    pub fn max_size(&self) -> Option<u64> {
+        match self {
+            Self::Never => None,
+            Self::Time { .. } => None,
+            Self::Size { max_size, .. } => Some(*max_size),
+            Self::Lines { .. } => None,
+            Self::Both { max_size, .. } => Some(*max_size),
+        }
+    }
+
+    /// Get the maximum number of records per file for line-based and hybrid rotation.
+    pub fn max_lines(&self) -> Option<u64> {
+        match self {
+            Self::Lines { max_lines, .. } => Some(*max_lines),
+            Self::Both { max_lines, .. } => *max_lines,
+            _ => None,
+        }
+    }
+
+    /// Get the maximum number of files to keep.
+    pub fn max_files(&self) -> Option<usize> {
+        match self {
+            Self::Never => None,
+            Self::Time { .. } => None,
+            Self::Size { max_files, .. } => Some(*max_files),
+            Self::Lines { max_files, .. } => Some(*max_files),
+            Self::Both { max_files, .. } => Some(*max_files),
+        }
+    }
+
+    /// Get the time period for time-based and hybrid rotation.
+    pub fn period(&self) -> Option<RotationPeriod> {
+        match self {
+            Self::Never => None,
+            Self::Time { period } => Some(*period),
+            Self::Size { .. } => None,
+            Self::Lines { .. } => None,
+            Self::Both { period, .. } => Some(*period),
+        }
+    }
+
+    /// Check if this trigger includes size-based rotation.
+    pub fn has_size_rotation(&self) -> bool {
+        matches!(self, Self::Size { .. } | Self::Both { .. })
+    }
+}
+
+/// Retention policy for time-suffixed log files.
+///
+/// Applies to time-based and hybrid rotation, where every period produces a new
+/// file (e.g. `app.log.2026-01-09`) together with its size-rotated siblings
+/// (`app.log.2026-01-09.1`, ...). Files of the current period are never removed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct Retention {
+    /// Maximum number of past periods to keep.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_periods: Option<usize>,
+    /// Maximum age of a period, measured from the end of the period.
+    /// Can be specified as a number of seconds or a string with units (s/m/h/d/w), e.g. "7d".
+    #[serde(
+        default,
+        with = "opt_duration",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub max_age: Option<Duration>,
+}
+
+impl Retention {
+    /// Keep at most `max_periods` past periods.
+    pub fn periods(max_periods: usize) -> Self {
+        Self {
+            max_periods: Some(max_periods),
+            max_age: None,
+        }
+    }
+
+    /// Keep periods that ended less than `max_age` ago.
+    pub fn max_age(max_age: Duration) -> Self {
+        Self {
+            max_periods: None,
+            max_age: Some(max_age),
+        }
+    }
+
+    /// Check if any retention limit is configured.
+    pub fn is_enabled(&self) -> bool {
+        self.max_periods.is_some() || self.max_age.is_some()
+    }
+}
+
+/// How the active file is moved aside during size-based rotation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RotationStrategy {
+    /// Copy the active file to `.1`, then truncate it in place.
+    ///
+    /// The active file keeps its identity, so `tail -f` keeps following it,
+    /// at the cost of copying the whole file on every rotation.
+    #[default]
+    CopyTruncate,
+    /// Rename the active file to `.1` and open a fresh one.
+    ///
+    /// Rotation is a constant-time atomic rename; tools following the file
+    /// must reopen it by name (e.g. `tail -F`).
+    Rename,
+}
+
+/// What to do with a record larger than the maximum file size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OversizedRecord {
+    /// Write the record whole into a file of its own.
+    #[default]
+    OwnFile,
+    /// Cut the record to the maximum file size, ending it with a
+    /// `[truncated]` marker. The rest is dropped up to the record's newline,
+    /// or until the record is twice the maximum size if no newline comes.
+    Truncate,
+}
+
+impl OversizedRecord {
+    /// Marker ending a truncated record, before its newline.
+    pub const MARKER: &'static [u8] = b"[truncated]";
+}
+
+/// Compression applied to rotated log files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Compression {
+    /// Keep rotated files uncompressed.
+    #[default]
+    None,
+    /// Compress rotated files with gzip (`.gz`, requires the `gzip` feature).
+    Gzip,
+    /// Compress rotated files with zstd (`.zst`, requires the `zstd` feature).
+    Zstd,
+}
+
+impl Compression {
+    /// All file extensions produced by compression, without the leading dot.
+    pub const EXTENSIONS: [&'static str; 2] = ["gz", "zst"];
+
+    /// Get the file extension for compressed files, without the leading dot.
+    pub fn extension(&self) -> Option<&'static str> {
+        match self {
+            Self::None => None,
+            Self::Gzip => Some("gz"),
+            Self::Zstd => Some("zst"),
+        }
+    }
+
+    /// Check if the compression algorithm was compiled in.
+    pub fn is_supported(&self) -> bool {
+        match self {
+            Self::None => true,
+            Self::Gzip => cfg!(feature = "gzip"),
+            Self::Zstd => cfg!(feature = "zstd"),
+        }
+    }
+
+    /// Split a file name into its uncompressed name and whether it was compressed.
+    pub fn strip_extension(name: &str) -> (&str, bool) {
+        Self::EXTENSIONS
+            .iter()
+            .find_map(|ext| {
+                name.strip_suffix(ext)
+                    .and_then(|rest| rest.strip_suffix('.'))
+            })
+            .map_or((name, false), |stem| (stem, true))
+    }
+}
+
+/// First day of the week for weekly rotation.
+///
+/// In config, written as the day's name, e.g. `sunday` or `sun`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Weekday {
+    #[default]
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+impl Weekday {
+    const ALL: [Self; 7] = [
+        Self::Monday,
+        Self::Tuesday,
+        Self::Wednesday,
+        Self::Thursday,
+        Self::Friday,
+        Self::Saturday,
+        Self::Sunday,
+    ];
+
+    /// Number of days since Monday (0 for Monday).
+    #[cfg(feature = "time")]
+    fn number_days_from_monday(self) -> u8 {
+        self as u8
+    }
+
+    #[cfg(feature = "time")]
+    fn to_time(self) -> time::Weekday {
+        time::Weekday::Monday.nth_next(self.number_days_from_monday())
+    }
+}
+
+impl FromStr for Weekday {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> crate::Result<Self> {
+        let name = s.trim().to_ascii_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|day| {
+                let full = day.to_string();
+                name == full || name == full[..3]
+            })
+            .ok_or_else(|| {
+                crate::Error::Config(format!(
+                    "invalid weekday `{}`: expected a day name like monday or mon",
+                    s.trim()
+                ))
+            })
+    }
+}
+
+impl fmt::Display for Weekday {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Monday => "monday",
+            Self::Tuesday => "tuesday",
+            Self::Wednesday => "wednesday",
+            Self::Thursday => "thursday",
+            Self::Friday => "friday",
+            Self::Saturday => "saturday",
+            Self::Sunday => "sunday",
+        })
+    }
+}
+
+/// How the files of weekly rotation are named.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum WeekSuffix {
+    /// The date the week starts, e.g. `2026-01-05` (`date` in config).
+    #[default]
+    Date,
+    /// The ISO 8601 week containing the day the week starts, e.g. `2026-W02`
+    /// (`iso` in config). Near New Year the ISO week-numbering year may differ
+    /// from the calendar year: the week starting Monday 2025-12-29 is `2026-W01`.
+    IsoWeek,
+}
+
+/// Time periods for log rotation.
+///
+/// In config, a period is a string: `never`, `hourly`, `daily`, `weekly`,
+/// `monthly`, an interval such as `15m`, or a cron expression such as `0 3 * * *`.
+/// Weekly rotation takes an optional start day and suffix style, e.g.
+/// `weekly(sunday)`, `weekly(iso)` or `weekly(sunday, iso)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RotationPeriod {
+    /// Never rotate.
+    Never,
+    /// Rotate every hour.
+    Hourly,
+    /// Rotate every day.
+    Daily,
+    /// Rotate every week, starting on Monday, with files named after the
+    /// start date.
+    Weekly,
+    /// Rotate every week, starting on `start`, with files named according to
+    /// `suffix`.
+    WeeklyOn {
+        /// The first day of the week.
+        start: Weekday,
+        /// How files are named.
+        suffix: WeekSuffix,
+    },
+    /// Rotate every month.
+    Monthly,
+    /// Rotate at a fixed interval, counted in whole seconds from the Unix epoch
+    /// in local time, so that e.g. `15m` rotates at :00, :15, :30 and :45.
+    Every(Duration),
+    /// Rotate on a cron-style schedule.
+    Cron(CronSchedule),
+}
+
+/// Smallest time unit a period's suffix must show.
+#[cfg(feature = "time")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SuffixPrecision {
+    Month,
+    Day,
+    Hour,
+    Minute,
+    Second,
+    IsoWeek,
+}
+
+#[cfg(feature = "time")]
+impl SuffixPrecision {
+    /// Format `at` with this precision; finer units are ignored.
+    fn format(self, at: OffsetDateTime) -> String {
+        use time::macros::format_description;
+
+        // Parsed at compile time, rather than on every call
+        let description = match self {
+            Self::Month => format_description!("[year]-[month]"),
+            Self::Day => format_description!("[year]-[month]-[day]"),
+            Self::Hour => format_description!("[year]-[month]-[day]T[hour]"),
+            Self::Minute => format_description!("[year]-[month]-[day]T[hour]-[minute]"),
+            Self::Second => {
+                format_description!("[year]-[month]-[day]T[hour]-[minute]-[second]")
+            }
+            Self::IsoWeek => format_description!("[year base:iso_week]-W[week_number repr:iso]"),
+        };
+        at.format(description).unwrap()
+    }
+}
+
+impl RotationPeriod {
+    /// Weekly rotation starting on `start`, with files named according to `suffix`.
+    pub fn weekly(start: Weekday, suffix: WeekSuffix) -> Self {
+        if start == Weekday::Monday && suffix == WeekSuffix::Date {
+            Self::Weekly
+        } else {
+            Self::WeeklyOn { start, suffix }
+        }
+    }
+
+    /// Get the start day and suffix style of weekly rotation.
+    #[cfg(feature = "time")]
+    fn week(&self) -> Option<(Weekday, WeekSuffix)> {
+        match *self {
+            Self::Weekly => Some((Weekday::Monday, WeekSuffix::Date)),
+            Self::WeeklyOn { start, suffix } => Some((start, suffix)),
+            _ => None,
+        }
+    }
+
+    /// Get the time suffix for the current period.
+    pub fn get_suffix(&self) -> String {
+        self.get_suffix_with(&SystemClock)
+    }
+
+    /// Get the time suffix for the current period in local time, reading the
+    /// time and the local offset from `clock` (UTC if the offset is unknown).
+    #[cfg(feature = "time")]
+    pub fn get_suffix_with(&self, clock: &dyn Clock) -> String {
+        let now = clock.now();
+        let offset = clock
+            .local_offset_at(now)
+            .and_then(|seconds| time::UtcOffset::from_whole_seconds(seconds).ok())
+            .unwrap_or(time::UtcOffset::UTC);
+        self.suffix_at(OffsetDateTime::from(now).to_offset(offset))
+    }
+
+    /// Get the time suffix for the period containing `now`.
+    #[cfg(feature = "time")]
+    pub fn suffix_at(&self, now: OffsetDateTime) -> String {
+        match self.period_start(now) {
+            Some(start) => self.precision().format(start),
+            None => String::new(),
+        }
+    }
+
+    /// Get the start of the period containing `now`.
+    #[cfg(feature = "time")]
+    pub fn period_start(&self, now: OffsetDateTime) -> Option<OffsetDateTime> {
+        let midnight = now.replace_time(time::Time::MIDNIGHT);
+        match self {
+            Self::Never => None,
+            Self::Hourly => Some(midnight.replace_hour(now.hour()).ok()?),
+            Self::Daily => Some(midnight),
+            Self::Weekly | Self::WeeklyOn { .. } => {
+                let (start, _) = self.week()?;
+                let days = (now.weekday().number_days_from_monday() + 7
+                    - start.number_days_from_monday())
+                    % 7;
+                midnight.checked_sub(time::Duration::days(days as i64))
+            }
+            Self::Monthly => Some(midnight.replace_day(1).ok()?),
+            Self::Every(interval) => {
+                let interval = interval.as_secs().max(1) as i64;
+                let local = now.unix_timestamp() + now.offset().whole_seconds() as i64;
+                let start =
+                    local - local.rem_euclid(interval) - now.offset().whole_seconds() as i64;
+                Some(
+                    OffsetDateTime::from_unix_timestamp(start)
+                        .ok()?
+                        .to_offset(now.offset()),
+                )
+            }
+            // Before the first firing in range, everything belongs to one period
+            Self::Cron(schedule) => Some(schedule.previous(now).unwrap_or(midnight)),
+        }
+    }
+
+    /// Smallest time unit the suffix needs to tell periods apart.
+    #[cfg(feature = "time")]
+    fn precision(&self) -> SuffixPrecision {
+        match self {
+            Self::Never | Self::Daily | Self::Weekly => SuffixPrecision::Day,
+            Self::WeeklyOn { suffix, .. } => match suffix {
+                WeekSuffix::Date => SuffixPrecision::Day,
+                WeekSuffix::IsoWeek => SuffixPrecision::IsoWeek,
+            },
+            Self::Hourly => SuffixPrecision::Hour,
+            Self::Monthly => SuffixPrecision::Month,
+            Self::Every(interval) => match interval.as_secs() {
+                secs if secs.is_multiple_of(24 * 60 * 60) => SuffixPrecision::Day,
+                secs if secs.is_multiple_of(60 * 60) => SuffixPrecision::Hour,
+                secs if secs.is_multiple_of(60) => SuffixPrecision::Minute,
+                _ => SuffixPrecision::Second,
+            },
+            Self::Cron(schedule) if schedule.is_daily() => SuffixPrecision::Day,
+            Self::Cron(schedule) if schedule.is_hourly() => SuffixPrecision::Hour,
+            Self::Cron(_) => SuffixPrecision::Minute,
+        }
+    }
+
+    /// Parse a suffix produced by [`get_suffix`](Self::get_suffix) back into the
+    /// start of its period, using `offset` as the time zone of the suffix.
+    ///
+    /// Returns `None` if `suffix` is not a valid suffix for this period.
+    #[cfg(feature = "time")]
+    pub fn parse_suffix(&self, suffix: &str, offset: time::UtcOffset) -> Option<OffsetDateTime> {
+        fn number(s: &str, digits: usize) -> Option<u8> {
+            if s.len() != digits || !s.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
+            }
+            s.parse().ok()
+        }
+
+        if *self == Self::Never {
+            return None;
+        }
+
+        if let Some((start, WeekSuffix::IsoWeek)) = self.week() {
+            let (year, week) = suffix.split_once("-W")?;
+            if year.len() != 4 || !year.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
+            }
+            let date = time::Date::from_iso_week_date(
+                year.parse().ok()?,
+                number(week, 2)?,
+                start.to_time(),
+            )
+            .ok()?;
+            let start = date.midnight().assume_offset(offset);
+            return (self.suffix_at(start) == suffix).then_some(start);
+        }
+
+        let (date_part, time_part) = match suffix.split_once('T') {
+            Some((date_part, time_part)) => (date_part, Some(time_part)),
+            None => (suffix, None),
+        };
+
+        let mut parts = date_part.split('-');
+        let year = parts.next()?;
+        let year = if year.len() == 4 && year.bytes().all(|b| b.is_ascii_digit()) {
+            year.parse().ok()?
+        } else {
+            return None;
+        };
+        let month = number(parts.next()?, 2)?;
+        let day = parts.next().map_or(Some(1), |day| number(day, 2))?;
+        if parts.next().is_some() {
+            return None;
+        }
+
+        let mut hms = [0u8; 3];
+        if let Some(time_part) = time_part {
+            let mut parts = time_part.split('-');
+            for value in &mut hms {
+                match parts.next() {
+                    Some(part) => *value = number(part, 2)?,
+                    None => break,
+                }
+            }
+            if parts.next().is_some() {
+                return None;
+            }
+        }
+
+        let month = time::Month::try_from(month).ok()?;
+        let date = time::Date::from_calendar_date(year, month, day).ok()?;
+        let start = date
+            .with_hms(hms[0], hms[1], hms[2])
+            .ok()?
+            .assume_offset(offset);
+
+        // Reject suffixes that don't name the start of a period (e.g. a weekly
+        // suffix that isn't a Monday), or that use another precision.
+        (self.suffix_at(start) == suffix).then_some(start)
+    }
+
+    /// Get the end of the period starting at `start`.
+    #[cfg(feature = "time")]
+    pub fn period_end(&self, start: OffsetDateTime) -> Option<OffsetDateTime> {
         match self {
             Self::Never => None,
-            Self::Time { .. } => None,
-            Self::Size { max_files, .. } => Some(*max_files),
-            Self::Both { max_files, .. } => Some(*max_files),
+            Self::Hourly => start.checked_add(time::Duration::HOUR),
+            Self::Daily => start.checked_add(time::Duration::DAY),
+            Self::Weekly | Self::WeeklyOn { .. } => start.checked_add(time::Duration::WEEK),
+            Self::Monthly => {
+                let (year, month) = match start.month() {
+                    time::Month::December => (start.year() + 1, time::Month::January),
+                    month => (start.year(), month.next()),
+                };
+                let date = time::Date::from_calendar_date(year, month, 1).ok()?;
+                Some(start.replace_date(date))
+            }
+            Self::Every(interval) => {
+                start.checked_add(time::Duration::seconds(interval.as_secs().max(1) as i64))
+            }
+            Self::Cron(schedule) => schedule.next(start),
         }
     }
 
-    /// Check if this trigger includes size-based rotation.
-    pub fn has_size_rotation(&self) -> bool {
-        matches!(self, Self::Size { .. } | Self::Both { .. })
+    /// Get the time suffix for the current period (no-op without time feature).
+    #[cfg(not(feature = "time"))]
+    pub fn get_suffix_with(&self, _clock: &dyn Clock) -> String {
+        String::new()
     }
 }
 
-/// Time periods for log rotation.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(rename_all = "snake_case")]
-pub enum RotationPeriod {
-    /// Never rotate.
-    Never,
-    /// Rotate every hour.
-    Hourly,
-    /// Rotate every day.
-    Daily,
-    /// Rotate every week.
-    Weekly,
-    /// Rotate every month.
-    Monthly,
+impl FromStr for RotationPeriod {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> crate::Result<Self> {
+        let s = s.trim();
+        match s.to_ascii_lowercase().as_str() {
+            "never" => Ok(Self::Never),
+            "hourly" => Ok(Self::Hourly),
+            "daily" => Ok(Self::Daily),
+            "weekly" => Ok(Self::Weekly),
+            "monthly" => Ok(Self::Monthly),
+            lower if lower.starts_with("weekly(") => parse_weekly(s),
+            _ if s.contains(char::is_whitespace) => CronSchedule::new(s).map(Self::Cron),
+            _ => match parse_duration(s) {
+                Ok(interval) if interval.is_zero() => Err(crate::Error::Config(
+                    "rotation interval must not be zero".to_string(),
+                )),
+                Ok(interval) if interval.subsec_nanos() != 0 => Err(crate::Error::Config(format!(
+                    "rotation interval `{}` must be a whole number of seconds",
+                    s
+                ))),
+                Ok(interval) => Ok(Self::Every(interval)),
+                Err(e) => Err(crate::Error::Config(format!(
+                    "invalid rotation period `{}`: expected never/hourly/daily/weekly/monthly, \
+                     an interval like \"15m\", or a cron expression like \"0 3 * * *\" ({})",
+                    s, e
+                ))),
+            },
+        }
+    }
 }
 
-impl RotationPeriod {
-    /// Get the time suffix for the current period.
-    #[cfg(feature = "time")]
-    pub fn get_suffix(&self) -> String {
-        let now = OffsetDateTime::now_local().unwrap_or_else(|_| OffsetDateTime::now_utc());
+/// Parse a weekly period with options, e.g. `weekly(sunday, iso)`.
+fn parse_weekly(s: &str) -> crate::Result<RotationPeriod> {
+    let invalid = |reason: String| {
+        crate::Error::Config(format!(
+            "invalid weekly rotation `{}`: {}; expected e.g. weekly(sunday), weekly(iso) \
+             or weekly(sunday, iso)",
+            s, reason
+        ))
+    };
+    let options = s["weekly(".len()..]
+        .strip_suffix(')')
+        .ok_or_else(|| invalid("missing `)`".to_string()))?;
+
+    let (mut start, mut suffix) = (None, None);
+    for option in options.split(',').map(str::trim) {
+        match option.to_ascii_lowercase().as_str() {
+            "date" | "iso" if suffix.is_some() => {
+                return Err(invalid("more than one suffix style".to_string()));
+            }
+            "date" => suffix = Some(WeekSuffix::Date),
+            "iso" => suffix = Some(WeekSuffix::IsoWeek),
+            _ if start.is_some() => return Err(invalid("more than one start day".to_string())),
+            _ => {
+                let day = option
+                    .parse()
+                    .map_err(|_| invalid(format!("unknown option `{}`", option)))?;
+                start = Some(day);
+            }
+        }
+    }
 
+    Ok(RotationPeriod::weekly(
+        start.unwrap_or_default(),
+        suffix.unwrap_or_default(),
+    ))
+}
+
+impl fmt::Display for RotationPeriod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Self::Never => String::new(),
-            Self::Hourly => now
-                .format(&time::format_description::parse("[year]-[month]-[day]T[hour]").unwrap())
-                .unwrap(),
-            Self::Daily => now
-                .format(&time::format_description::parse("[year]-[month]-[day]").unwrap())
-                .unwrap(),
-            Self::Weekly => {
-                let week_start =
-                    now - time::Duration::days(now.weekday().number_days_from_monday() as i64);
-                week_start
-                    .format(&time::format_description::parse("[year]-[month]-[day]").unwrap())
-                    .unwrap()
+            Self::Never => f.write_str("never"),
+            Self::Hourly => f.write_str("hourly"),
+            Self::Daily => f.write_str("daily"),
+            Self::Weekly => f.write_str("weekly"),
+            Self::WeeklyOn { start, suffix } => {
+                let mut options = Vec::new();
+                if *start != Weekday::Monday {
+                    options.push(start.to_string());
+                }
+                if *suffix == WeekSuffix::IsoWeek {
+                    options.push("iso".to_string());
+                }
+                if options.is_empty() {
+                    f.write_str("weekly")
+                } else {
+                    write!(f, "weekly({})", options.join(", "))
+                }
             }
-            Self::Monthly => now
-                .format(&time::format_description::parse("[year]-[month]").unwrap())
-                .unwrap(),
+            Self::Monthly => f.write_str("monthly"),
+            Self::Every(interval) => f.write_str(&format_duration(*interval)),
+            Self::Cron(schedule) => write!(f, "{}", schedule),
         }
     }
+}
 
-    /// Get the time suffix for the current period (no-op without time feature).
-    #[cfg(not(feature = "time"))]
-    pub fn get_suffix(&self) -> String {
-        String::new()
+impl Serialize for RotationPeriod {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for RotationPeriod {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(de::Error::custom)
     }
 }
 
@@ -309,6 +1326,42 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_rotation_trigger_lines_deserialize() {
+        let yaml = r#"
+type: lines
+max_lines: 1000
+max_files: 3
+"#;
+        let trigger: RotationTrigger = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(trigger, RotationTrigger::lines(1000, 3));
+        assert_eq!(trigger.max_lines(), Some(1000));
+        assert_eq!(trigger.max_size(), None);
+
+        let trigger: RotationTrigger = serde_yaml::from_str("lines").unwrap();
+        assert_eq!(trigger.max_lines(), Some(100_000));
+
+        assert!(serde_yaml::from_str::<RotationTrigger>("type: lines\nmax_files: 3").is_err());
+        assert!(serde_yaml::from_str::<RotationTrigger>("type: lines\nmax_lines: 0").is_err());
+
+        #[cfg(feature = "time")]
+        {
+            let yaml = r#"
+type: both
+period: daily
+max_size: 10M
+max_lines: 500
+"#;
+            let trigger: RotationTrigger = serde_yaml::from_str(yaml).unwrap();
+            assert_eq!(
+                trigger,
+                RotationTrigger::both(RotationPeriod::Daily, 10 * 1024 * 1024, 5)
+                    .with_max_lines(500)
+            );
+            assert_eq!(trigger.max_lines(), Some(500));
+        }
+    }
+
     #[test]
     fn test_rotation_trigger_deserialize() {
         // Test deserializing "never"
@@ -437,12 +1490,131 @@ max_files: 10
                 RotationTrigger::Both {
                     period: RotationPeriod::Hourly,
                     max_size: 512 * 1024,
+                    max_lines: None,
                     max_files: 10
                 }
             );
         }
     }
 
+    #[test]
+    fn test_rotation_trigger_documented_shapes() {
+        let yaml = r#"
+size:
+  max_size: "10M"
+  max_backups: 5
+"#;
+        let trigger: RotationTrigger = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(trigger, RotationTrigger::size(10 * 1024 * 1024, 5));
+
+        let trigger: RotationTrigger = serde_yaml::from_str("size: 10M").unwrap();
+        assert_eq!(trigger, RotationTrigger::size(10 * 1024 * 1024, 5));
+
+        let trigger: RotationTrigger = serde_yaml::from_str("lines: 1000").unwrap();
+        assert_eq!(trigger, RotationTrigger::lines(1000, 5));
+
+        let yaml = "type: size\nmax_size: 1M\nmax_backups: 3";
+        let trigger: RotationTrigger = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(trigger, RotationTrigger::size(1024 * 1024, 3));
+
+        let trigger: RotationTrigger = toml::from_str("[size]\nmax_size = \"1G\"").unwrap();
+        assert_eq!(trigger, RotationTrigger::size(1024 * 1024 * 1024, 5));
+
+        for invalid in [
+            "sized: { max_size: 10M }",
+            "size: { type: lines }",
+            "size: [10M]",
+            "size:",
+        ] {
+            assert!(
+                serde_yaml::from_str::<RotationTrigger>(invalid).is_err(),
+                "{}",
+                invalid
+            );
+        }
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn test_rotation_trigger_period_shapes() {
+        let trigger: RotationTrigger = serde_yaml::from_str("daily:").unwrap();
+        assert_eq!(trigger, RotationTrigger::time(RotationPeriod::Daily));
+        assert_eq!(
+            parse_rotation(
+                serde_json::json!({ "daily": { "max_backups": 7 } }),
+                PlainSizeUnit::default()
+            ),
+            Ok((RotationTrigger::time(RotationPeriod::Daily), Some(7)))
+        );
+
+        let trigger: RotationTrigger = serde_yaml::from_str("hourly:").unwrap();
+        assert_eq!(trigger, RotationTrigger::time(RotationPeriod::Hourly));
+
+        let trigger: RotationTrigger = serde_yaml::from_str("time: weekly").unwrap();
+        assert_eq!(trigger, RotationTrigger::time(RotationPeriod::Weekly));
+
+        let yaml = "daily: { max_size: 100M, max_lines: 1000, max_backups: 3 }";
+        let trigger: RotationTrigger = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(
+            trigger,
+            RotationTrigger::both(RotationPeriod::Daily, 100 * 1024 * 1024, 3).with_max_lines(1000)
+        );
+
+        assert!(serde_yaml::from_str::<RotationTrigger>("daily: { period: hourly }").is_err());
+
+        // Backups of a time-based trigger are retention, which only a file
+        // config can hold; size and line limits need a hybrid trigger
+        for invalid in [
+            "daily: { max_backups: 7 }",
+            "time: { period: daily, max_files: 7 }",
+            "type: time\nperiod: daily\nmax_backups: 7",
+            "type: time\nperiod: daily\nmax_size: 10M",
+            "type: time\nperiod: daily\nmax_lines: 1000",
+            "time: { period: hourly, max_size: 10M }",
+        ] {
+            assert!(
+                serde_yaml::from_str::<RotationTrigger>(invalid).is_err(),
+                "{}",
+                invalid
+            );
+        }
+        assert!(toml::from_str::<RotationTrigger>("[daily]\nmax_backups = 7").is_err());
+    }
+
+    #[test]
+    fn test_rotation_trigger_serde_roundtrip() {
+        #[allow(unused_mut)]
+        let mut triggers = vec![
+            RotationTrigger::Never,
+            RotationTrigger::size(10 * 1024 * 1024, 5),
+            RotationTrigger::size(1000, 2),
+            RotationTrigger::lines(100_000, 3),
+        ];
+        #[cfg(feature = "time")]
+        triggers.extend([
+            RotationTrigger::time(RotationPeriod::Daily),
+            RotationTrigger::time(RotationPeriod::weekly(Weekday::Sunday, WeekSuffix::IsoWeek)),
+            RotationTrigger::time(RotationPeriod::Every(Duration::from_secs(15 * 60))),
+            RotationTrigger::both(RotationPeriod::Hourly, 512 * 1024, 10),
+            RotationTrigger::both(RotationPeriod::Monthly, 1024 * 1024, 4).with_max_lines(500),
+        ]);
+
+        for trigger in triggers {
+            let yaml = serde_yaml::to_string(&trigger).unwrap();
+            let parsed: RotationTrigger = serde_yaml::from_str(&yaml).unwrap();
+            assert_eq!(parsed, trigger, "{}", yaml);
+
+            let json = serde_json::to_string(&trigger).unwrap();
+            let parsed: RotationTrigger = serde_json::from_str(&json).unwrap();
+            assert_eq!(parsed, trigger, "{}", json);
+
+            // Sizes are written with a unit, so they don't depend on `plain_sizes`
+            let value = serde_yaml::from_str(&yaml).unwrap();
+            let (parsed, _) = parse_rotation(value, PlainSizeUnit::Bytes).unwrap();
+            assert_eq!(parsed, trigger, "{}", yaml);
+        }
+    }
+
     #[cfg(feature = "time")]
     #[test]
     fn test_rotation_period_suffixes() {
@@ -462,6 +1634,394 @@ max_files: 10
         assert_eq!(monthly.chars().filter(|c| *c == '-').count(), 1);
     }
 
+    #[cfg(feature = "time")]
+    #[test]
+    fn test_rotation_period_suffix_with_clock() {
+        // Noon UTC, and local time is UTC until an offset is set
+        let clock = crate::clock::ManualClock::from_unix_timestamp(1_769_860_800);
+        assert_eq!(RotationPeriod::Daily.get_suffix_with(&clock), "2026-01-31");
+        assert_eq!(RotationPeriod::Monthly.get_suffix_with(&clock), "2026-01");
+
+        clock.advance(Duration::from_secs(24 * 60 * 60));
+        assert_eq!(RotationPeriod::Daily.get_suffix_with(&clock), "2026-02-01");
+        assert_eq!(RotationPeriod::Monthly.get_suffix_with(&clock), "2026-02");
+
+        // The local offset comes from the clock too
+        clock.set_local_offset(std::time::SystemTime::UNIX_EPOCH, 13 * 60 * 60);
+        assert_eq!(RotationPeriod::Daily.get_suffix_with(&clock), "2026-02-02");
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(2 * 1024 * 1024 * 1024), "2G");
+        assert_eq!(format_size(1536 * 1024 * 1024), "1536M");
+        assert_eq!(format_size(512 * 1024), "512K");
+        assert_eq!(format_size(3 * 1024 * 1024 * 1024 * 1024), "3T");
+        assert_eq!(format_size(1000), "1000B");
+        assert_eq!(format_size(0), "0B");
+        for size in [10 * 1024 * 1024, 1000, 0] {
+            assert_eq!(
+                parse_size_with(&format_size(size), PlainSizeUnit::Bytes),
+                Ok(size)
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_size() {
+        let (k, m, g) = (1024, 1024 * 1024, 1024 * 1024 * 1024);
+        for (input, bytes) in [
+            ("512B", 512),
+            ("64k", 64 * k),
+            ("64 KB", 64 * k),
+            ("64KiB", 64 * k),
+            ("10MB", 10 * m),
+            ("10mib", 10 * m),
+            ("1.5G", g + g / 2),
+            ("2GB", 2 * g),
+            ("1T", 1024 * g),
+            ("0.5K", 512),
+        ] {
+            assert_eq!(
+                parse_size_with(input, PlainSizeUnit::Bytes),
+                Ok(bytes),
+                "{:?}",
+                input
+            );
+        }
+
+        // A number without a unit follows the configured mode
+        assert_eq!(parse_size_with("1024", PlainSizeUnit::Kilobytes), Ok(m));
+        assert_eq!(parse_size_with("1024", PlainSizeUnit::Bytes), Ok(k));
+
+        for invalid in [
+            "",
+            "K",
+            "1.5",
+            "1.5B",
+            "0.1K",
+            "1..5G",
+            "1.G",
+            ".5G",
+            "-1K",
+            "10 MBs",
+            "1e6",
+            "1P",
+            "99999999999T",
+        ] {
+            let error = parse_size_with(invalid, PlainSizeUnit::Bytes).unwrap_err();
+            assert!(
+                error.starts_with("invalid size"),
+                "{:?}: {}",
+                invalid,
+                error
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("30"), Ok(Duration::from_secs(30)));
+        assert_eq!(parse_duration("30s"), Ok(Duration::from_secs(30)));
+        assert_eq!(parse_duration("15m"), Ok(Duration::from_secs(15 * 60)));
+        assert_eq!(parse_duration("12H"), Ok(Duration::from_secs(12 * 60 * 60)));
+        assert_eq!(
+            parse_duration("7d"),
+            Ok(Duration::from_secs(7 * 24 * 60 * 60))
+        );
+        assert_eq!(
+            parse_duration("2w"),
+            Ok(Duration::from_secs(14 * 24 * 60 * 60))
+        );
+        assert_eq!(parse_duration("250ms"), Ok(Duration::from_millis(250)));
+        assert_eq!(parse_duration("1.5h"), Ok(Duration::from_secs(90 * 60)));
+        assert_eq!(parse_duration("0.5"), Ok(Duration::from_millis(500)));
+        assert_eq!(
+            parse_duration("7 days"),
+            Ok(Duration::from_secs(7 * 24 * 60 * 60))
+        );
+        assert_eq!(parse_duration("10 Minutes"), Ok(Duration::from_secs(600)));
+        for invalid in ["", "7x", "d", "1mo", "2 years", "1.0001s", "1..5s", "-5s"] {
+            let error = parse_duration(invalid).unwrap_err();
+            assert!(
+                error.starts_with("invalid duration"),
+                "{:?}: {}",
+                invalid,
+                error
+            );
+        }
+
+        assert_eq!(format_duration(Duration::from_secs(7 * 24 * 60 * 60)), "1w");
+        assert_eq!(format_duration(Duration::from_secs(36 * 60 * 60)), "36h");
+        assert_eq!(format_duration(Duration::from_secs(90)), "90s");
+        assert_eq!(format_duration(Duration::ZERO), "0s");
+        assert_eq!(format_duration(Duration::from_millis(1500)), "1500ms");
+        assert_eq!(
+            parse_duration(&format_duration(Duration::from_millis(1500))),
+            Ok(Duration::from_millis(1500))
+        );
+    }
+
+    #[test]
+    fn test_rotation_strategy_deserialize() {
+        let strategy: RotationStrategy = serde_yaml::from_str("rename").unwrap();
+        assert_eq!(strategy, RotationStrategy::Rename);
+        let strategy: RotationStrategy = serde_yaml::from_str("copy_truncate").unwrap();
+        assert_eq!(strategy, RotationStrategy::CopyTruncate);
+        assert_eq!(RotationStrategy::default(), RotationStrategy::CopyTruncate);
+    }
+
+    #[test]
+    fn test_compression_extensions() {
+        assert_eq!(Compression::None.extension(), None);
+        assert_eq!(Compression::Gzip.extension(), Some("gz"));
+        assert_eq!(Compression::Zstd.extension(), Some("zst"));
+
+        assert_eq!(
+            Compression::strip_extension("app.log.1.gz"),
+            ("app.log.1", true)
+        );
+        assert_eq!(
+            Compression::strip_extension("app.log.2026-01-09.zst"),
+            ("app.log.2026-01-09", true)
+        );
+        assert_eq!(
+            Compression::strip_extension("app.log.1"),
+            ("app.log.1", false)
+        );
+        assert_eq!(
+            Compression::strip_extension("app.loggz"),
+            ("app.loggz", false)
+        );
+
+        let compression: Compression = serde_yaml::from_str("gzip").unwrap();
+        assert_eq!(compression, Compression::Gzip);
+    }
+
+    #[test]
+    fn test_retention_serde_roundtrip() {
+        let retention = Retention {
+            max_periods: Some(7),
+            max_age: Some(Duration::from_secs(30 * 24 * 60 * 60)),
+        };
+        let yaml = serde_yaml::to_string(&retention).unwrap();
+        assert!(yaml.contains("30d"));
+        let parsed: Retention = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(parsed, retention);
+
+        let parsed: Retention = serde_yaml::from_str("max_age: 3600").unwrap();
+        assert_eq!(parsed, Retention::max_age(Duration::from_secs(3600)));
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn test_rotation_period_parse_suffix() {
+        use time::macros::datetime;
+
+        let offset = time::UtcOffset::UTC;
+        let now = datetime!(2026-01-09 13:45:10 UTC);
+
+        for period in [
+            RotationPeriod::Hourly,
+            RotationPeriod::Daily,
+            RotationPeriod::Weekly,
+            RotationPeriod::Monthly,
+        ] {
+            let start = period.parse_suffix(&period.suffix_at(now), offset).unwrap();
+            assert!(start <= now);
+            assert!(period.period_end(start).unwrap() > now);
+        }
+
+        assert_eq!(
+            RotationPeriod::Hourly.parse_suffix("2026-01-09T13", offset),
+            Some(datetime!(2026-01-09 13:00 UTC))
+        );
+        assert_eq!(
+            RotationPeriod::Weekly.parse_suffix("2026-01-05", offset),
+            Some(datetime!(2026-01-05 0:00 UTC))
+        );
+        // Not a Monday
+        assert_eq!(
+            RotationPeriod::Weekly.parse_suffix("2026-01-06", offset),
+            None
+        );
+        assert_eq!(
+            RotationPeriod::Daily.parse_suffix("2026-1-09", offset),
+            None
+        );
+        assert_eq!(
+            RotationPeriod::Daily.parse_suffix("2026-01-09.1", offset),
+            None
+        );
+        assert_eq!(
+            RotationPeriod::Monthly.parse_suffix("2026-01-01", offset),
+            None
+        );
+        assert_eq!(RotationPeriod::Never.parse_suffix("", offset), None);
+
+        assert_eq!(
+            RotationPeriod::Monthly.period_end(datetime!(2026-12-01 0:00 UTC)),
+            Some(datetime!(2027-01-01 0:00 UTC))
+        );
+    }
+
+    #[test]
+    fn test_rotation_period_from_str() {
+        for (input, period) in [
+            ("daily", RotationPeriod::Daily),
+            ("Hourly", RotationPeriod::Hourly),
+            ("15m", RotationPeriod::Every(Duration::from_secs(15 * 60))),
+            ("90", RotationPeriod::Every(Duration::from_secs(90))),
+            (
+                "0 3 * * *",
+                RotationPeriod::Cron(CronSchedule::new("0 3 * * *").unwrap()),
+            ),
+            ("weekly(monday, date)", RotationPeriod::Weekly),
+            (
+                "Weekly(Sun, ISO)",
+                RotationPeriod::weekly(Weekday::Sunday, WeekSuffix::IsoWeek),
+            ),
+        ] {
+            assert_eq!(input.parse::<RotationPeriod>().unwrap(), period);
+        }
+
+        assert!("0s".parse::<RotationPeriod>().is_err());
+        assert!("fortnightly".parse::<RotationPeriod>().is_err());
+        assert!("0 3 * *".parse::<RotationPeriod>().is_err());
+        for invalid in [
+            "weekly(",
+            "weekly(funday)",
+            "weekly(iso, date)",
+            "weekly(mon, sun)",
+        ] {
+            assert!(invalid.parse::<RotationPeriod>().is_err(), "{:?}", invalid);
+        }
+
+        // Serialized as the same strings
+        for input in [
+            "never",
+            "weekly",
+            "weekly(sunday)",
+            "weekly(iso)",
+            "weekly(saturday, iso)",
+            "15m",
+            "1d",
+            "0 3 * * *",
+            "*/5 * * * 1-5",
+        ] {
+            let period: RotationPeriod = serde_yaml::from_str(&format!("'{}'", input)).unwrap();
+            assert_eq!(period.to_string(), input);
+            let yaml = serde_yaml::to_string(&period).unwrap();
+            assert_eq!(
+                serde_yaml::from_str::<RotationPeriod>(&yaml).unwrap(),
+                period
+            );
+        }
+
+        let yaml = r#"
+type: both
+period: "15m"
+max_size: "1M"
+max_files: 3
+"#;
+        let trigger: RotationTrigger = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(
+            trigger.period(),
+            Some(RotationPeriod::Every(Duration::from_secs(15 * 60)))
+        );
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn test_rotation_period_weekly_options() {
+        use time::macros::datetime;
+
+        let offset = time::UtcOffset::UTC;
+        let sunday = RotationPeriod::weekly(Weekday::Sunday, WeekSuffix::Date);
+        let iso = RotationPeriod::weekly(Weekday::Monday, WeekSuffix::IsoWeek);
+        let sunday_iso = RotationPeriod::weekly(Weekday::Sunday, WeekSuffix::IsoWeek);
+
+        // Friday
+        let now = datetime!(2026-01-09 13:45 UTC);
+        assert_eq!(RotationPeriod::Weekly.suffix_at(now), "2026-01-05");
+        assert_eq!(sunday.suffix_at(now), "2026-01-04");
+        assert_eq!(iso.suffix_at(now), "2026-W02");
+        assert_eq!(
+            sunday.period_end(sunday.period_start(now).unwrap()),
+            Some(datetime!(2026-01-11 0:00 UTC))
+        );
+
+        // Across New Year, the ISO year is the one of the week's Thursday
+        let now = datetime!(2025-12-31 8:00 UTC);
+        assert_eq!(iso.suffix_at(now), "2026-W01");
+        assert_eq!(
+            iso.parse_suffix("2026-W01", offset),
+            Some(datetime!(2025-12-29 0:00 UTC))
+        );
+        let now = datetime!(2027-01-02 8:00 UTC);
+        assert_eq!(iso.suffix_at(now), "2026-W53");
+        assert_eq!(iso.parse_suffix("2027-W53", offset), None);
+
+        // A week starting on Sunday is named after the ISO week of that Sunday
+        let now = datetime!(2026-01-03 8:00 UTC);
+        assert_eq!(sunday_iso.suffix_at(now), "2025-W52");
+        assert_eq!(
+            sunday_iso.parse_suffix("2025-W52", offset),
+            Some(datetime!(2025-12-28 0:00 UTC))
+        );
+
+        assert_eq!(sunday.parse_suffix("2026-01-05", offset), None);
+        assert_eq!(iso.parse_suffix("2026-01-05", offset), None);
+        assert_eq!(iso.parse_suffix("2026-W2", offset), None);
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn test_rotation_period_every_and_cron_suffixes() {
+        use time::macros::{datetime, offset};
+
+        let now = datetime!(2026-01-09 13:47:10 UTC);
+        let quarter = RotationPeriod::Every(Duration::from_secs(15 * 60));
+        assert_eq!(quarter.suffix_at(now), "2026-01-09T13-45");
+        assert_eq!(
+            quarter.period_end(datetime!(2026-01-09 13:45 UTC)),
+            Some(datetime!(2026-01-09 14:00 UTC))
+        );
+        // Aligned to local time
+        assert_eq!(
+            RotationPeriod::Every(Duration::from_secs(6 * 60 * 60))
+                .suffix_at(datetime!(2026-01-09 1:00 +8)),
+            "2026-01-09T00"
+        );
+        assert_eq!(
+            RotationPeriod::Every(Duration::from_secs(90)).suffix_at(now),
+            "2026-01-09T13-46-30"
+        );
+
+        let backup = RotationPeriod::Cron(CronSchedule::new("0 3 * * *").unwrap());
+        assert_eq!(backup.suffix_at(now), "2026-01-09T03");
+        assert_eq!(
+            backup.suffix_at(datetime!(2026-01-09 2:59 UTC)),
+            "2026-01-08T03"
+        );
+        let frequent = RotationPeriod::Cron(CronSchedule::new("*/20 * * * *").unwrap());
+        assert_eq!(frequent.suffix_at(now), "2026-01-09T13-40");
+
+        for period in [quarter, backup, frequent] {
+            let suffix = period.suffix_at(now);
+            let start = period.parse_suffix(&suffix, time::UtcOffset::UTC).unwrap();
+            assert!(start <= now);
+            assert!(period.period_end(start).unwrap() > now);
+            // Later periods sort after earlier ones
+            let next = period.suffix_at(period.period_end(start).unwrap());
+            assert!(next > suffix);
+        }
+
+        // Not the start of a period
+        assert_eq!(quarter.parse_suffix("2026-01-09T13-40", offset!(UTC)), None);
+        assert_eq!(backup.parse_suffix("2026-01-09", offset!(UTC)), None);
+    }
+
     #[cfg(feature = "time")]
     #[test]
     fn test_rotation_period_never() {
@@ -494,6 +2054,7 @@ max_files: 10
             RotationTrigger::Both {
                 period: RotationPeriod::Daily,
                 max_size: 2048,
+                max_lines: None,
                 max_files: 10
             }
         );
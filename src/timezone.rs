@@ -0,0 +1,248 @@
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::sync::atomic::AtomicI32;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+
+use crate::clock::Clock;
+use crate::{Error, Result};
+
+/// Time zone for rotation boundaries, file suffixes and record timestamps.
+///
+/// In config, written as `utc`, `local`, or a fixed offset such as `+08:00`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Timezone {
+    /// Coordinated Universal Time.
+    Utc,
+    /// The local time zone of the system.
+    ///
+    /// Determining the local offset can fail, notably in multi-threaded
+    /// processes on Unix, in which case UTC is used. Set a fixed offset to
+    /// avoid depending on it.
+    #[default]
+    Local,
+    /// A fixed offset from UTC, in seconds east of UTC.
+    Fixed(i32),
+}
+
+impl Timezone {
+    /// Largest supported offset, just under a day.
+    const MAX_OFFSET: i32 = 24 * 60 * 60 - 1;
+
+    /// Create a fixed offset from UTC in hours and minutes, e.g. `(8, 0)` or `(-5, -30)`.
+    pub fn fixed(hours: i8, minutes: i8) -> Result<Self> {
+        let seconds = i32::from(hours) * 3600 + i32::from(minutes) * 60;
+        if minutes.abs() >= 60 || (hours != 0 && minutes != 0 && hours.signum() != minutes.signum())
+        {
+            return Err(Error::Config(format!(
+                "invalid time zone offset {}h{}m",
+                hours, minutes
+            )));
+        }
+        if seconds.abs() > Self::MAX_OFFSET {
+            return Err(Error::Config(format!(
+                "time zone offset {}h{}m out of range",
+                hours, minutes
+            )));
+        }
+        Ok(Self::Fixed(seconds))
+    }
+
+    /// Resolve `Local` to the current local offset, falling back to `Utc`.
+    ///
+    /// Call this once at initialization, so that every part of the logging
+    /// setup agrees on the offset even if later lookups would fail.
+    pub fn resolve(self) -> Self {
+        match self {
+            #[cfg(feature = "time")]
+            Self::Local => match time::UtcOffset::current_local_offset() {
+                Ok(offset) => Self::Fixed(offset.whole_seconds()),
+                Err(_) => Self::Utc,
+            },
+            other => other,
+        }
+    }
+
+    /// Get the offset from UTC, resolving `Local` if needed.
+    #[cfg(feature = "time")]
+    pub fn offset(self) -> time::UtcOffset {
+        match self.resolve() {
+            Self::Fixed(seconds) => {
+                time::UtcOffset::from_whole_seconds(seconds).unwrap_or(time::UtcOffset::UTC)
+            }
+            _ => time::UtcOffset::UTC,
+        }
+    }
+}
+
+/// A time zone resolved once and shared by the file writer and the record
+/// timestamps, so that both use the same offset.
+///
+/// With `Local`, the file writer looks up the offset again whenever it starts
+/// a new file, e.g. at a period boundary after a DST change, and timestamps
+/// switch to it at the same time.
+#[derive(Debug, Clone)]
+pub(crate) struct SharedTimezone {
+    timezone: Timezone,
+    /// Offset in use, in seconds east of UTC.
+    #[cfg_attr(not(feature = "time"), allow(dead_code))]
+    offset: Arc<AtomicI32>,
+}
+
+impl SharedTimezone {
+    /// Resolve `timezone`, looking up the local offset now with `clock`;
+    /// if it can't be determined, UTC is used.
+    pub(crate) fn new(timezone: Timezone, clock: &dyn Clock) -> Self {
+        let offset = match timezone {
+            Timezone::Utc => 0,
+            Timezone::Local => clock.local_offset_at(clock.now()).unwrap_or(0),
+            Timezone::Fixed(seconds) => seconds,
+        };
+        Self {
+            timezone,
+            offset: Arc::new(AtomicI32::new(offset)),
+        }
+    }
+
+    /// Get the configured time zone.
+    #[cfg_attr(not(feature = "time"), allow(dead_code))]
+    pub(crate) fn timezone(&self) -> Timezone {
+        self.timezone
+    }
+
+    /// Get the offset in use.
+    #[cfg(feature = "time")]
+    pub(crate) fn offset(&self) -> time::UtcOffset {
+        time::UtcOffset::from_whole_seconds(self.offset.load(std::sync::atomic::Ordering::Relaxed))
+            .unwrap_or(time::UtcOffset::UTC)
+    }
+
+    /// Switch to `offset`, e.g. when the file writer starts a new file after
+    /// a DST change.
+    #[cfg(feature = "time")]
+    pub(crate) fn set_offset(&self, offset: time::UtcOffset) {
+        self.offset
+            .store(offset.whole_seconds(), std::sync::atomic::Ordering::Relaxed);
+    }
+}
+
+impl FromStr for Timezone {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        match s.to_ascii_lowercase().as_str() {
+            "utc" | "z" => return Ok(Self::Utc),
+            "local" => return Ok(Self::Local),
+            _ => {}
+        }
+
+        let invalid = || {
+            Error::Config(format!(
+                "invalid time zone `{}`: expected utc, local, or an offset like +08:00",
+                s
+            ))
+        };
+
+        let (sign, rest) = match s.as_bytes().first() {
+            Some(b'+') => (1, &s[1..]),
+            Some(b'-') => (-1, &s[1..]),
+            _ => return Err(invalid()),
+        };
+        let (hours, minutes) = match rest.split_once(':') {
+            Some((hours, minutes)) => (hours, minutes),
+            None if rest.len() == 4 => rest.split_at(2),
+            None => (rest, "0"),
+        };
+        let number = |s: &str| -> Option<i8> {
+            if s.is_empty() || s.len() > 2 || !s.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
+            }
+            s.parse().ok()
+        };
+        let hours = number(hours).ok_or_else(invalid)?;
+        let minutes = number(minutes).ok_or_else(invalid)?;
+
+        Self::fixed(sign * hours, sign * minutes)
+    }
+}
+
+impl fmt::Display for Timezone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Utc => f.write_str("utc"),
+            Self::Local => f.write_str("local"),
+            Self::Fixed(seconds) => {
+                let sign = if *seconds < 0 { '-' } else { '+' };
+                let minutes = seconds.abs() / 60;
+                write!(f, "{}{:02}:{:02}", sign, minutes / 60, minutes % 60)
+            }
+        }
+    }
+}
+
+impl Serialize for Timezone {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Timezone {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timezone_from_str() {
+        assert_eq!("utc".parse::<Timezone>().unwrap(), Timezone::Utc);
+        assert_eq!("Local".parse::<Timezone>().unwrap(), Timezone::Local);
+        assert_eq!(
+            "+08:00".parse::<Timezone>().unwrap(),
+            Timezone::Fixed(8 * 3600)
+        );
+        assert_eq!(
+            "-05:30".parse::<Timezone>().unwrap(),
+            Timezone::Fixed(-(5 * 3600 + 30 * 60))
+        );
+        assert_eq!("+0530".parse::<Timezone>().unwrap(), Timezone::Fixed(19800));
+        assert_eq!("+8".parse::<Timezone>().unwrap(), Timezone::Fixed(8 * 3600));
+
+        for invalid in ["", "08:00", "+24:00", "+08:60", "+08:00:00", "CET", "+x"] {
+            assert!(invalid.parse::<Timezone>().is_err(), "{:?}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_timezone_serde_roundtrip() {
+        for input in ["utc", "local", "+08:00", "-05:30", "+00:00"] {
+            let timezone: Timezone = serde_yaml::from_str(&format!("'{}'", input)).unwrap();
+            assert_eq!(timezone.to_string(), input);
+            let yaml = serde_yaml::to_string(&timezone).unwrap();
+            assert_eq!(serde_yaml::from_str::<Timezone>(&yaml).unwrap(), timezone);
+        }
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn test_timezone_offset() {
+        assert_eq!(Timezone::Utc.offset(), time::UtcOffset::UTC);
+        assert_eq!(
+            Timezone::fixed(-5, -30).unwrap().offset(),
+            time::UtcOffset::from_hms(-5, -30, 0).unwrap()
+        );
+        assert_ne!(Timezone::Local.resolve(), Timezone::Local);
+    }
+}
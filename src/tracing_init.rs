@@ -2,13 +2,19 @@
 use crate::FileLogConfig;
 #[cfg(feature = "file")]
 use crate::RotatingWriter;
-use crate::{Error, LogConfig, Result};
+use crate::clock::SystemClock;
+use crate::hooks::HookList;
+use crate::timezone::SharedTimezone;
+use crate::{Error, LogConfig, LoggingHandle, Result};
 #[cfg(feature = "file")]
 use once_cell::sync::Lazy;
 #[cfg(feature = "file")]
 use std::sync::Mutex;
 #[cfg(feature = "time")]
-use tracing_subscriber::fmt::time::OffsetTime;
+use tracing_subscriber::fmt::{
+    format::Writer,
+    time::{FormatTime, OffsetTime},
+};
 use tracing_subscriber::{EnvFilter, Layer, layer::SubscriberExt, util::SubscriberInitExt};
 
 /// Custom RFC3339 format with 3-digit subseconds (milliseconds).
@@ -21,22 +27,38 @@ const RFC3339_MS: &[time::format_description::FormatItem<'static>] = time::macro
 static LOG_GUARD: Lazy<Mutex<Option<tracing_appender::non_blocking::WorkerGuard>>> =
     Lazy::new(|| Mutex::new(None));
 
-/// Create a local timezone timer for tracing-subscriber.
+/// Timer for tracing-subscriber, in the time zone shared with the file writer.
 #[cfg(feature = "time")]
-fn create_timer() -> OffsetTime<&'static [time::format_description::FormatItem<'static>]> {
-    match time::UtcOffset::current_local_offset() {
-        Ok(offset) => OffsetTime::new(offset, RFC3339_MS),
-        Err(_) => OffsetTime::new(time::UtcOffset::UTC, RFC3339_MS),
+struct Timer(SharedTimezone);
+
+#[cfg(feature = "time")]
+impl FormatTime for Timer {
+    fn format_time(&self, w: &mut Writer<'_>) -> std::fmt::Result {
+        OffsetTime::new(self.0.offset(), RFC3339_MS).format_time(w)
     }
 }
 
+/// Create a timer in the configured time zone for tracing-subscriber.
+#[cfg(feature = "time")]
+fn create_timer(timezone: &SharedTimezone) -> Timer {
+    Timer(timezone.clone())
+}
+
 /// Initialize logging with the given configuration and optional CLI verbosity override.
 pub fn init_logging(config: &LogConfig) -> Result<()> {
+    init_logging_with_hooks(config, &HookList::default()).map(|_| ())
+}
+
+/// Initialize logging, registering rotation hooks on the file writer.
+#[cfg_attr(not(feature = "file"), allow(unused_variables))]
+pub(crate) fn init_logging_with_hooks(
+    config: &LogConfig,
+    hooks: &HookList,
+) -> Result<LoggingHandle> {
     let env_filter = EnvFilter::try_new(&config.level).map_err(|e| Error::Init(e.to_string()))?;
 
-    // Create timer for local timezone when time feature is enabled
-    #[cfg(feature = "time")]
-    let _timer = create_timer();
+    // Resolve the time zone once, so timestamps and rotation agree on it
+    let timezone = SharedTimezone::new(config.timezone, &SystemClock);
 
     // Determine effective console and file settings based on features
     let effective_console = config.console;
@@ -45,41 +67,59 @@ pub fn init_logging(config: &LogConfig) -> Result<()> {
     #[cfg(not(feature = "file"))]
     let effective_file: &Option<crate::FileLogConfig> = &None;
 
-    match (effective_console, effective_file.as_ref()) {
+    let writer = match (effective_console, effective_file.as_ref()) {
         (true, Some(_)) => {
             // Console and file - only available when file feature is enabled
             #[cfg(feature = "file")]
-            init_console_and_file(config, effective_file.as_ref().unwrap(), env_filter)?;
+            let writer = Some(init_console_and_file(
+                config,
+                effective_file.as_ref().unwrap(),
+                env_filter,
+                &timezone,
+                hooks,
+            )?);
             #[cfg(not(feature = "file"))]
-            init_console_only(config, env_filter)?;
+            let writer = init_console_only(config, env_filter, &timezone).map(|_| None)?;
+            writer
         }
         (true, None) => {
             // Console only
-            init_console_only(config, env_filter)?;
+            init_console_only(config, env_filter, &timezone)?;
+            None
         }
         (false, Some(_)) => {
             // File only - only available when file feature is enabled
             #[cfg(feature = "file")]
-            init_file_only(config, effective_file.as_ref().unwrap(), env_filter)?;
+            let writer = Some(init_file_only(
+                config,
+                effective_file.as_ref().unwrap(),
+                env_filter,
+                &timezone,
+                hooks,
+            )?);
             #[cfg(not(feature = "file"))]
-            init_no_logging(env_filter)?;
+            let writer = init_no_logging(env_filter).map(|_| None)?;
+            writer
         }
         (false, None) => {
             // No logging
             init_no_logging(env_filter)?;
+            None
         }
-    }
+    };
 
-    Ok(())
+    Ok(LoggingHandle::new(writer))
 }
 
-/// Initialize console and file logging.
+/// Initialize console and file logging, returning the file writer.
 #[cfg(feature = "file")]
 fn init_console_and_file(
     config: &LogConfig,
     file_config: &FileLogConfig,
     env_filter: EnvFilter,
-) -> Result<()> {
+    timezone: &SharedTimezone,
+    hooks: &HookList,
+) -> Result<RotatingWriter> {
     let fmt_layer_builder = tracing_subscriber::fmt::layer()
         .with_target(config.target)
         .with_thread_ids(config.thread_ids)
@@ -93,18 +133,17 @@ fn init_console_and_file(
     let fmt_layer = if config.format == "json" {
         let layer = fmt_layer_builder.json();
         #[cfg(feature = "time")]
-        let layer = layer.with_timer(create_timer());
+        let layer = layer.with_timer(create_timer(timezone));
         layer.boxed()
     } else {
         let layer = fmt_layer_builder;
         #[cfg(feature = "time")]
-        let layer = layer.with_timer(create_timer());
+        let layer = layer.with_timer(create_timer(timezone));
         layer.boxed()
     };
 
-    let writer =
-        RotatingWriter::new(&file_config.path, file_config.rotation.clone()).map_err(Error::Io)?;
-    let (non_blocking, guard) = tracing_appender::non_blocking(writer);
+    let writer = create_writer(file_config, timezone, hooks)?;
+    let (non_blocking, guard) = tracing_appender::non_blocking(writer.clone());
 
     *LOG_GUARD.lock().unwrap() = Some(guard);
 
@@ -117,7 +156,7 @@ fn init_console_and_file(
             .with_thread_names(config.thread_names)
             .json();
         #[cfg(feature = "time")]
-        let layer = layer.with_timer(create_timer());
+        let layer = layer.with_timer(create_timer(timezone));
         layer.boxed()
     } else {
         let layer = tracing_subscriber::fmt::layer()
@@ -127,7 +166,7 @@ fn init_console_and_file(
             .with_thread_ids(config.thread_ids)
             .with_thread_names(config.thread_names);
         #[cfg(feature = "time")]
-        let layer = layer.with_timer(create_timer());
+        let layer = layer.with_timer(create_timer(timezone));
         layer.boxed()
     };
 
@@ -138,11 +177,35 @@ fn init_console_and_file(
         .try_init()
         .map_err(|e| Error::Init(e.to_string()))?;
 
-    Ok(())
+    Ok(writer)
+}
+
+/// Create the file writer in the resolved time zone, with the rotation hooks.
+#[cfg(feature = "file")]
+fn create_writer(
+    file_config: &FileLogConfig,
+    timezone: &SharedTimezone,
+    hooks: &HookList,
+) -> Result<RotatingWriter> {
+    let writer = RotatingWriter::from_config_with_shared_timezone(
+        file_config,
+        timezone.clone(),
+        std::sync::Arc::new(SystemClock),
+    )
+    .map_err(Error::Io)?;
+    Ok(hooks.0.iter().fold(writer, |writer, hook| {
+        let hook = std::sync::Arc::clone(hook);
+        writer.on_rotate(move |event| hook(event))
+    }))
 }
 
 /// Initialize console-only logging.
-fn init_console_only(config: &LogConfig, env_filter: EnvFilter) -> Result<()> {
+#[cfg_attr(not(feature = "time"), allow(unused_variables))]
+fn init_console_only(
+    config: &LogConfig,
+    env_filter: EnvFilter,
+    timezone: &SharedTimezone,
+) -> Result<()> {
     let fmt_layer_builder = tracing_subscriber::fmt::layer()
         .with_target(config.target)
         .with_thread_ids(config.thread_ids)
@@ -156,12 +219,12 @@ fn init_console_only(config: &LogConfig, env_filter: EnvFilter) -> Result<()> {
     let fmt_layer = if config.format == "json" {
         let layer = fmt_layer_builder.json();
         #[cfg(feature = "time")]
-        let layer = layer.with_timer(create_timer());
+        let layer = layer.with_timer(create_timer(timezone));
         layer.boxed()
     } else {
         let layer = fmt_layer_builder;
         #[cfg(feature = "time")]
-        let layer = layer.with_timer(create_timer());
+        let layer = layer.with_timer(create_timer(timezone));
         layer.boxed()
     };
 
@@ -174,16 +237,17 @@ fn init_console_only(config: &LogConfig, env_filter: EnvFilter) -> Result<()> {
     Ok(())
 }
 
-/// Initialize file-only logging.
+/// Initialize file-only logging, returning the file writer.
 #[cfg(feature = "file")]
 fn init_file_only(
     config: &LogConfig,
     file_config: &FileLogConfig,
     env_filter: EnvFilter,
-) -> Result<()> {
-    let writer =
-        RotatingWriter::new(&file_config.path, file_config.rotation.clone()).map_err(Error::Io)?;
-    let (non_blocking, guard) = tracing_appender::non_blocking(writer);
+    timezone: &SharedTimezone,
+    hooks: &HookList,
+) -> Result<RotatingWriter> {
+    let writer = create_writer(file_config, timezone, hooks)?;
+    let (non_blocking, guard) = tracing_appender::non_blocking(writer.clone());
 
     *LOG_GUARD.lock().unwrap() = Some(guard);
 
@@ -196,7 +260,7 @@ fn init_file_only(
             .with_thread_names(config.thread_names)
             .json();
         #[cfg(feature = "time")]
-        let layer = layer.with_timer(create_timer());
+        let layer = layer.with_timer(create_timer(timezone));
         layer.boxed()
     } else {
         let layer = tracing_subscriber::fmt::layer()
@@ -206,7 +270,7 @@ fn init_file_only(
             .with_thread_ids(config.thread_ids)
             .with_thread_names(config.thread_names);
         #[cfg(feature = "time")]
-        let layer = layer.with_timer(create_timer());
+        let layer = layer.with_timer(create_timer(timezone));
         layer.boxed()
     };
 
@@ -216,7 +280,7 @@ fn init_file_only(
         .try_init()
         .map_err(|e| Error::Init(e.to_string()))?;
 
-    Ok(())
+    Ok(writer)
 }
 
 /// Initialize with no output (for testing or when logging is disabled).
@@ -290,6 +354,86 @@ mod tests {
         assert!(result.is_ok() || result.is_err());
     }
 
+    #[cfg(all(feature = "time", feature = "file"))]
+    #[test]
+    fn test_writer_and_timer_share_timezone() {
+        use std::io::Write;
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        let file_config = crate::FileLogConfig::new(dir.path().join("app.log"))
+            .with_rotation_trigger(crate::RotationTrigger::time(crate::RotationPeriod::Daily));
+        let timezone = SharedTimezone::new(crate::Timezone::fixed(14, 0).unwrap(), &SystemClock);
+        let timer = create_timer(&timezone);
+        let timestamp = || {
+            let mut timestamp = String::new();
+            timer.format_time(&mut Writer::new(&mut timestamp)).unwrap();
+            timestamp
+        };
+
+        let before = timestamp();
+        let mut writer = create_writer(&file_config, &timezone, &HookList::default()).unwrap();
+        writer.write_all(b"message\n").unwrap();
+        writer.flush().unwrap();
+        let after = timestamp();
+
+        // The file is named after the date of the timestamps
+        assert!(before.ends_with("+14:00"), "{}", before);
+        assert!(
+            [&before, &after].iter().any(|timestamp| dir
+                .path()
+                .join(format!("app.log.{}", &timestamp[..10]))
+                .exists()),
+            "{} {}",
+            before,
+            after
+        );
+    }
+
+    #[cfg(all(feature = "time", feature = "file"))]
+    #[test]
+    fn test_timer_follows_dst_change_with_files() {
+        use std::io::Write;
+        use std::time::{Duration, SystemTime};
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        let at = |secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs);
+        // Central European time, switching to summer time at 2026-03-29T01:00Z
+        let clock = crate::clock::ManualClock::from_unix_timestamp(1_774_740_600);
+        clock.set_local_offset(SystemTime::UNIX_EPOCH, 3600);
+        clock.set_local_offset(at(1_774_746_000), 7200);
+
+        let file_config = crate::FileLogConfig::new(dir.path().join("app.log"))
+            .with_rotation_trigger(crate::RotationTrigger::time(crate::RotationPeriod::Daily));
+        let timezone = SharedTimezone::new(crate::Timezone::Local, &clock);
+        let timer = create_timer(&timezone);
+        let offset = || {
+            let mut timestamp = String::new();
+            timer.format_time(&mut Writer::new(&mut timestamp)).unwrap();
+            timestamp[timestamp.len() - 6..].to_string()
+        };
+        let mut writer = RotatingWriter::from_config_with_shared_timezone(
+            &file_config,
+            timezone.clone(),
+            std::sync::Arc::new(clock.clone()),
+        )
+        .unwrap();
+
+        // Sunday 00:30 at +01:00
+        writer.write_all(b"sunday\n").unwrap();
+        assert_eq!(offset(), "+01:00");
+
+        // After the change, the day's file and its timestamps keep the offset
+        clock.set(at(1_774_785_600));
+        writer.write_all(b"sunday noon\n").unwrap();
+        assert_eq!(offset(), "+01:00");
+
+        // Monday 00:00 at +02:00 starts a new file
+        clock.set(at(1_774_821_600));
+        writer.write_all(b"monday\n").unwrap();
+        assert_eq!(offset(), "+02:00");
+        assert!(dir.path().join("app.log.2026-03-30").exists());
+    }
+
     #[cfg(feature = "time")]
     #[test]
     fn test_timezone_in_console_output() {
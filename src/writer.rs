@@ -1,38 +1,290 @@
-use std::fs::{File, OpenOptions};
-use std::io::{self, Write};
-use std::path::PathBuf;
-use std::sync::{Arc, Mutex};
-
-use crate::{RotationPeriod, RotationTrigger};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, Weak};
+use std::time::{Duration, Instant, SystemTime};
+
+#[cfg(feature = "time")]
+use crate::RotationPeriod;
+use crate::clock::{Clock, SystemClock};
+use crate::compress::Compressor;
+use crate::failure::{Attempt, WriteFailures};
+use crate::header::HeaderWriter;
+use crate::hooks::{RotationEvent, RotationHooks, RotationReason};
+use crate::permissions::Permissions;
+use crate::timezone::SharedTimezone;
+use crate::{
+    Compression, Durability, FileLogConfig, FileNaming, OversizedRecord, Retention,
+    RotationStrategy, RotationTrigger, Timezone,
+};
 
 /// State of the current log file.
 #[derive(Debug)]
 pub struct FileState {
-    /// The open file handle.
-    pub file: File,
+    /// The open file handle, behind the write buffer.
+    pub file: BufWriter<File>,
     /// Current size of the file in bytes.
     pub size: u64,
+    /// Number of complete records in the file (only counted for line-based rotation).
+    pub lines: u64,
     /// Time suffix for the current file (empty for size-only rotation).
     pub time_suffix: String,
+    /// When the period of the file ends, for time-based rotation.
+    pub(crate) rotate_at: Option<SystemTime>,
+    /// When the file was last checked for being moved or deleted.
+    pub(crate) checked_at: Instant,
+    /// When the file was last synced to disk.
+    pub(crate) synced_at: Instant,
+    /// Bytes written of a record whose newline hasn't been written yet.
+    pub(crate) record_len: u64,
+    /// Whether the rest of the current record is discarded.
+    pub(crate) truncating: bool,
+    /// Bytes of the header written when the file was created.
+    pub(crate) header_len: u64,
+}
+
+impl FileState {
+    /// Whether anything besides the header was written to the file.
+    fn has_records(&self) -> bool {
+        self.size > self.header_len
+    }
+
+    /// Whether the period of the file is over at `now`.
+    fn period_ended(&self, now: SystemTime) -> bool {
+        self.rotate_at.is_some_and(|at| now >= at)
+    }
+}
+
+/// A rotated file found next to the active log file.
+#[derive(Debug)]
+struct ArchiveFile {
+    /// Path of the file.
+    path: PathBuf,
+    /// Time suffix of the period the file belongs to (empty for size-only rotation).
+    #[cfg_attr(not(feature = "time"), allow(dead_code))]
+    suffix: String,
+    /// Index in the size rotation chain (`1` is the newest), if any.
+    index: Option<usize>,
+    /// Whether the file is compressed.
+    compressed: bool,
 }
 
 /// A writer that rotates log files based on size and/or time.
+///
+/// Clones share the same files and state, so a clone kept aside (e.g. in a
+/// signal handler) can [`reopen`](Self::reopen) the file that another clone is
+/// writing to.
+#[derive(Clone)]
 pub struct RotatingWriter {
+    inner: Arc<Inner>,
+}
+
+/// State shared by the clones of a [`RotatingWriter`].
+struct Inner {
     /// Base path for log files.
     base_path: PathBuf,
+    /// File name of the base path, used to derive the names of all log files.
+    base_name: String,
+    /// Naming scheme for active and rotated files.
+    naming: FileNaming,
     /// Rotation trigger configuration.
     trigger: RotationTrigger,
+    /// How the active file is moved aside during size-based rotation.
+    strategy: RotationStrategy,
+    /// Retention policy for time-suffixed files.
+    #[cfg_attr(not(feature = "time"), allow(dead_code))]
+    retention: Retention,
+    /// Time zone of rotation boundaries, shared with the record timestamps.
+    #[cfg_attr(not(feature = "time"), allow(dead_code))]
+    timezone: SharedTimezone,
+    /// Maximum total size of the active file and all rotated files.
+    max_total_size: Option<u64>,
+    /// Total size of rotated files as of the last scan (with a total size limit).
+    archive_size: AtomicU64,
+    /// How often to check whether the active file was replaced.
+    reopen_check: Duration,
+    /// Symlink kept pointing at the active file, if any.
+    #[cfg_attr(not(unix), allow(dead_code))]
+    symlink: Option<PathBuf>,
+    /// Directory rotated files are moved to, if not the log directory.
+    archive_dir: Option<PathBuf>,
+    /// Header written at the start of new files, if any.
+    header: Option<HeaderWriter>,
+    /// What to do with records larger than the maximum file size.
+    oversized: OversizedRecord,
+    /// How far records are pushed towards the disk on flush.
+    durability: Durability,
+    /// Capacity of the write buffer of each file.
+    buffer_size: usize,
+    /// Mode and ownership of created files and directories.
+    permissions: Permissions,
+    /// Lock file coordinating processes sharing the log files, in multi-process mode.
+    lock_file: Option<File>,
+    /// Failed writes, handled according to the configured policy.
+    failures: WriteFailures,
+    /// Hooks run after every rotation.
+    hooks: Arc<RotationHooks>,
+    /// Background compressor for rotated files, if compression is enabled.
+    compressor: OnceLock<Compressor>,
+    /// Source of the time for naming files and ending periods.
+    clock: Arc<dyn Clock>,
     /// Current file state, protected by mutex.
-    state: Arc<Mutex<Option<FileState>>>,
+    state: Mutex<Option<FileState>>,
 }
 
 impl RotatingWriter {
     /// Create a new rotating writer.
     pub fn new(base_path: &std::path::Path, trigger: RotationTrigger) -> io::Result<Self> {
-        let writer = Self {
-            base_path: base_path.to_path_buf(),
-            trigger,
-            state: Arc::new(Mutex::new(None)),
+        Self::from_config(&FileLogConfig::new(base_path).with_rotation_trigger(trigger))
+    }
+
+    /// Create a new rotating writer from a file logging configuration.
+    ///
+    /// Rotation boundaries use the local time zone.
+    pub fn from_config(config: &FileLogConfig) -> io::Result<Self> {
+        Self::from_config_with_timezone(config, Timezone::Local)
+    }
+
+    /// Create a new rotating writer whose rotation boundaries use `timezone`.
+    ///
+    /// With `Timezone::Local`, the offset is looked up again at every period
+    /// boundary, so that periods follow DST changes. If a lookup fails, the
+    /// offset at creation is used, or UTC if that failed too.
+    pub fn from_config_with_timezone(
+        config: &FileLogConfig,
+        timezone: Timezone,
+    ) -> io::Result<Self> {
+        Self::from_config_with_clock(config, timezone, Arc::new(SystemClock))
+    }
+
+    /// Create a new rotating writer that reads the time from `clock`, e.g. a
+    /// `ManualClock` to simulate period boundaries in tests.
+    pub fn from_config_with_clock(
+        config: &FileLogConfig,
+        timezone: Timezone,
+        clock: Arc<dyn Clock>,
+    ) -> io::Result<Self> {
+        let timezone = SharedTimezone::new(timezone, clock.as_ref());
+        Self::from_config_with_shared_timezone(config, timezone, clock)
+    }
+
+    /// Create a new rotating writer in a time zone already resolved for the
+    /// record timestamps.
+    pub(crate) fn from_config_with_shared_timezone(
+        config: &FileLogConfig,
+        timezone: SharedTimezone,
+        clock: Arc<dyn Clock>,
+    ) -> io::Result<Self> {
+        Ok(Self {
+            inner: Inner::open(config, timezone, clock)?,
+        })
+    }
+
+    /// Register a hook to run after every rotation.
+    ///
+    /// Hooks run in order on a background thread, so a slow hook never stalls
+    /// logging.
+    pub fn on_rotate<F>(self, hook: F) -> Self
+    where
+        F: Fn(&RotationEvent) + Send + Sync + 'static,
+    {
+        self.inner.hooks.add(Arc::new(hook));
+        self
+    }
+
+    /// Number of records dropped because they couldn't be written.
+    pub fn dropped_records(&self) -> u64 {
+        self.inner.failures.dropped()
+    }
+
+    /// Close the active file and open it again by name.
+    ///
+    /// Use this after an external tool such as logrotate moved or deleted the
+    /// file, e.g. from a `SIGHUP` handler. A missing file is recreated along
+    /// with its directory. Files are also reopened automatically when the
+    /// writer notices that the file was replaced.
+    pub fn reopen(&self) -> io::Result<()> {
+        let mut state = self.inner.state.lock().unwrap();
+        self.inner.reopen_locked(&mut state)
+    }
+
+    /// Rotate the active file now, regardless of the rotation trigger.
+    ///
+    /// The file is moved aside like in size-based rotation, with the same
+    /// naming, compression, retention and hooks. Triggers without a file
+    /// limit keep every file rotated this way, until the retention policy or
+    /// total size limit removes it. An active file without records (empty, or
+    /// holding just the header) is left as is.
+    pub fn rotate_now(&self) -> io::Result<()> {
+        let mut state = self.inner.state.lock().unwrap();
+        let _lock = self.inner.lock_processes()?;
+        if self.inner.lock_file.is_some() {
+            self.inner.catch_up(&mut state)?;
+        }
+        self.inner.prepare(&mut state, 0)?;
+        self.inner.rotate_manually(&mut state)
+    }
+}
+
+impl Inner {
+    /// Create the writer state and open the active file.
+    fn open(
+        config: &FileLogConfig,
+        timezone: SharedTimezone,
+        clock: Arc<dyn Clock>,
+    ) -> io::Result<Arc<Self>> {
+        let base_name = config
+            .path
+            .file_name()
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("log path {} has no file name", config.path.display()),
+                )
+            })?
+            .to_string_lossy()
+            .into_owned();
+
+        // Relative paths are resolved against the log directory
+        let resolve = |path: &PathBuf| match config.path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.join(path),
+            _ => path.clone(),
+        };
+
+        let permissions = Permissions::from_config(config);
+        let mut writer = Self {
+            base_path: config.path.clone(),
+            base_name,
+            naming: config.naming.clone(),
+            trigger: config.rotation.clone(),
+            strategy: config.strategy,
+            retention: config.retention,
+            timezone,
+            max_total_size: config.max_total_size,
+            symlink: config.symlink.as_ref().map(resolve),
+            archive_dir: config.archive_dir.as_ref().map(resolve),
+            header: config
+                .header
+                .clone()
+                .map(|header| HeaderWriter::new(header, config)),
+            reopen_check: config.reopen_check,
+            archive_size: AtomicU64::new(0),
+            compressor: OnceLock::new(),
+            oversized: config.oversized_records,
+            durability: config.durability,
+            // Records must reach the file before the lock is released
+            buffer_size: if config.multi_process {
+                0
+            } else {
+                usize::try_from(config.buffer_size).unwrap_or(usize::MAX)
+            },
+            permissions,
+            lock_file: None,
+            failures: WriteFailures::new(config.write_failure),
+            hooks: Arc::new(RotationHooks::default()),
+            clock,
+            state: Mutex::new(None),
         };
 
         // Ensure parent directory exists (create if necessary). This makes
@@ -41,435 +293,2940 @@ impl RotatingWriter {
         if let Some(parent) = writer.base_path.parent()
             && !parent.as_os_str().is_empty()
         {
-            std::fs::create_dir_all(parent)?;
+            writer.permissions.create_dir_all(parent)?;
+        }
+        if let Some(archive_dir) = &writer.archive_dir {
+            writer.permissions.create_dir_all(archive_dir)?;
         }
 
-        // Initialize with a file
-        writer.get_or_rotate(0)?;
+        let lock_path = config
+            .multi_process
+            .then(|| writer.log_dir().join(format!(".{}.lock", writer.base_name)));
+        if let Some(lock_path) = &lock_path {
+            let lock_file = writer
+                .permissions
+                .open_options()
+                .create(true)
+                .truncate(false)
+                .write(true)
+                .open(lock_path)?;
+            writer.permissions.apply_to_file(&lock_file)?;
+            writer.lock_file = Some(lock_file);
+        }
+
+        // Compressing in place of files other processes may have shifted
+        // relies on file identities
+        #[cfg(not(unix))]
+        if config.multi_process && config.compress != Compression::None {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "compression in multi-process mode is only supported on Unix",
+            ));
+        }
+
+        // The compressor gets hold of the writer to recheck the total size
+        let writer = Arc::new(writer);
+        if config.compress != Compression::None {
+            let owner = Arc::downgrade(&writer);
+            let compressor = Compressor::new(
+                config.compress,
+                permissions,
+                Arc::clone(&writer.hooks),
+                lock_path.as_deref(),
+                Box::new(move || Self::compressed(&owner)),
+            )?;
+            let _ = writer.compressor.set(compressor);
+        }
+        writer.initialize(config.rotate_on_start)?;
 
         Ok(writer)
     }
 
+    /// Bring the files within the total size limit once a rotated file has
+    /// been compressed, as files waiting for compression aren't counted.
+    fn compressed(owner: &Weak<Self>) {
+        let Some(writer) = owner.upgrade() else {
+            return;
+        };
+        if writer.max_total_size.is_none() {
+            return;
+        }
+        let state = writer.state.lock().unwrap();
+        let active_size = state.as_ref().map_or(0, |state| state.size);
+        let result = writer
+            .lock_processes()
+            .and_then(|_lock| writer.enforce_total_size(active_size));
+        if let Err(e) = result {
+            eprintln!("lazylog: failed to enforce the total size limit: {}", e);
+        }
+    }
+
+    /// Open the active file and tidy up after previous runs.
+    fn initialize(&self, rotate_on_start: bool) -> io::Result<()> {
+        let _lock = self.lock_processes()?;
+
+        // Initialize with a file
+        self.prepare(&mut self.state.lock().unwrap(), 0)?;
+
+        // Archive files rotated while no archive directory was configured
+        self.move_leftover_archives()?;
+
+        // Clean up periods that expired while the process wasn't running
+        self.prune_expired_periods()?;
+
+        // Pick up archives left uncompressed by a previous run
+        self.compress_leftover_archives()?;
+
+        // Start with a fresh file if requested
+        if rotate_on_start {
+            self.rotate_manually(&mut self.state.lock().unwrap())?;
+        }
+
+        // Bring the files within the total size limit
+        let active_size = self.active_size();
+        self.enforce_total_size(active_size)
+    }
+
     /// Get the current time suffix based on the rotation period.
     fn current_time_suffix(&self) -> String {
-        match &self.trigger {
-            RotationTrigger::Never => String::new(),
-            RotationTrigger::Time { period } => period.get_suffix(),
-            RotationTrigger::Size { .. } => String::new(),
-            RotationTrigger::Both { period, .. } => period.get_suffix(),
-        }
+        self.current_period().0
     }
 
-    /// Determine the actual file path for the current rotation.
-    fn current_file_path(&self) -> PathBuf {
-        let suffix = self.current_time_suffix();
-        if suffix.is_empty() {
-            self.base_path.clone()
-        } else {
-            // Time-based: append suffix like .2026-01-09
-            let path_str = self.base_path.to_string_lossy();
-            PathBuf::from(format!("{}.{}", path_str, suffix))
+    /// Get the current time in the writer's time zone.
+    #[cfg(feature = "time")]
+    fn now(&self) -> time::OffsetDateTime {
+        let now = self.clock.now();
+        time::OffsetDateTime::from(now).to_offset(self.offset_at(now))
+    }
+
+    /// Get the offset from UTC of the writer's time zone at `at`.
+    #[cfg(feature = "time")]
+    fn offset_at(&self, at: SystemTime) -> time::UtcOffset {
+        let local_offset = match self.timezone.timezone() {
+            Timezone::Local => self.clock.local_offset_at(at),
+            _ => None,
+        };
+        local_offset
+            .and_then(|seconds| time::UtcOffset::from_whole_seconds(seconds).ok())
+            .unwrap_or_else(|| self.timezone.offset())
+    }
+
+    /// Get the time suffix of the current period, and when the period ends
+    /// (empty and none without time-based rotation).
+    #[cfg(feature = "time")]
+    fn current_period(&self) -> (String, Option<SystemTime>) {
+        let Some(period) = self.trigger.period() else {
+            return (String::new(), None);
+        };
+        // Both from the same instant, so the file never outlives its period
+        let now = self.now();
+        let end = period
+            .period_start(now)
+            .and_then(|start| period.period_end(start))
+            .map(|end| {
+                // The period ends at a local time, whose offset may differ
+                // after a DST change. Keep the old one if the end would move
+                // back to or before now, e.g. into the skipped hour.
+                let moved = end.replace_offset(self.offset_at(end.into()));
+                if moved > now { moved } else { end }
+            });
+        (period.suffix_at(now), end.map(SystemTime::from))
+    }
+
+    /// Get the time suffix of the current period (no-op without time feature).
+    #[cfg(not(feature = "time"))]
+    fn current_period(&self) -> (String, Option<SystemTime>) {
+        (String::new(), None)
+    }
+
+    /// Determine the file path for the given time suffix.
+    fn file_path_for_suffix(&self, suffix: &str) -> PathBuf {
+        self.file_path(suffix, None)
+    }
+
+    /// Determine the file path for a time suffix (empty for none) and rotation index.
+    fn file_path(&self, suffix: &str, index: Option<usize>) -> PathBuf {
+        if suffix.is_empty() && index.is_none() {
+            return self.base_path.clone();
         }
+        // Time-based and rotated names, e.g. base.2026-01-09.1 with the default naming
+        self.base_path
+            .with_file_name(self.naming.file_name(&self.base_name, suffix, index))
     }
 
-    /// Check if rotation is needed based on current state and buffer size.
-    fn needs_rotation(&self, state: &FileState, buf_len: usize) -> bool {
-        match &self.trigger {
-            RotationTrigger::Never => false,
-            RotationTrigger::Time { period } => {
-                if *period == RotationPeriod::Never {
-                    return false;
-                }
-                let current_suffix = period.get_suffix();
-                current_suffix != state.time_suffix
-            }
-            RotationTrigger::Size { max_size, .. } => state.size + buf_len as u64 > *max_size,
-            RotationTrigger::Both {
-                period, max_size, ..
-            } => {
-                let time_trigger = if *period != RotationPeriod::Never {
-                    period.get_suffix() != state.time_suffix
-                } else {
-                    false
-                };
-                let size_trigger = state.size + buf_len as u64 > *max_size;
-                time_trigger || size_trigger
+    /// Determine the path of a rotated file for a time suffix (empty for none)
+    /// and rotation index, in the archive directory.
+    fn archive_path(&self, suffix: &str, index: Option<usize>) -> PathBuf {
+        match &self.archive_dir {
+            Some(archive_dir) => {
+                archive_dir.join(self.naming.file_name(&self.base_name, suffix, index))
             }
+            None => self.file_path(suffix, index),
         }
     }
 
-    /// Check if the base file exists and is within size limits
-    fn should_use_existing_file(&self) -> io::Result<bool> {
-        if !self.base_path.exists() {
-            return Ok(false);
+    /// Get the directory containing the log files.
+    fn log_dir(&self) -> &Path {
+        match self.base_path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        }
+    }
+
+    /// Get the directory containing the rotated files.
+    fn archive_dir(&self) -> &Path {
+        self.archive_dir
+            .as_deref()
+            .unwrap_or_else(|| self.log_dir())
+    }
+
+    /// Check if rotation is needed based on current state and buffer size.
+    ///
+    /// A new period takes precedence over the size limit, which takes
+    /// precedence over the record limit. Files only rotate
+    /// between complete records, and a file without records never rotates for
+    /// its size or record count, so that a record larger than the limit ends up
+    /// in a file of its own. A record that doesn't end is still bounded: it
+    /// goes on in a new file once its period has ended, or once it has grown
+    /// past the size limit.
+    fn needs_rotation(&self, state: &FileState, buf_len: usize) -> Option<RotationReason> {
+        if state.record_len > 0 {
+            return if state.period_ended(self.clock.now()) {
+                Some(RotationReason::Time)
+            } else if !state.truncating
+                && self
+                    .trigger
+                    .max_size()
+                    .is_some_and(|max_size| state.record_len > max_size)
+            {
+                Some(RotationReason::Size)
+            } else {
+                None
+            };
         }
 
+        // Comparing against the end of the period keeps formatting the time
+        // suffix off the write path
+        let time_trigger = || state.period_ended(self.clock.now());
+        let size_trigger =
+            |max_size: u64| state.has_records() && state.size + buf_len as u64 > max_size;
+        let lines_trigger = |max_lines: Option<u64>| {
+            state.has_records() && max_lines.is_some_and(|max| state.lines >= max)
+        };
+
         match &self.trigger {
-            RotationTrigger::Size { max_size, .. } | RotationTrigger::Both { max_size, .. } => {
-                let metadata = self.base_path.metadata()?;
-                let file_size = metadata.len();
-                Ok(file_size <= *max_size)
+            RotationTrigger::Never => None,
+            RotationTrigger::Time { .. } => time_trigger().then_some(RotationReason::Time),
+            RotationTrigger::Size { max_size, .. } => {
+                size_trigger(*max_size).then_some(RotationReason::Size)
+            }
+            RotationTrigger::Lines { max_lines, .. } => {
+                lines_trigger(Some(*max_lines)).then_some(RotationReason::Lines)
+            }
+            RotationTrigger::Both {
+                max_size,
+                max_lines,
+                ..
+            } => {
+                if time_trigger() {
+                    Some(RotationReason::Time)
+                } else if size_trigger(*max_size) {
+                    Some(RotationReason::Size)
+                } else {
+                    lines_trigger(*max_lines).then_some(RotationReason::Lines)
+                }
             }
-            _ => Ok(false), // For time-only or never rotation, don't use existing file
         }
     }
 
-    /// Perform size-based rotation by copying content instead of renaming.
+    /// Perform size-based rotation according to the rotation strategy.
+    ///
+    /// With copy-truncate, copies content: base.log -> base.log.1, then truncates
+    /// base.log to 0. This preserves the main log file for continuous monitoring
+    /// (e.g., tail -f). With rename, moves base.log -> base.log.1 and leaves the
+    /// fresh file to be created by the caller.
     ///
-    /// Copies content: base.log -> base.log.1, then truncates base.log to 0
-    /// This preserves the main log file for continuous monitoring (e.g., tail -f)
-    fn rotate_by_size(&self) -> io::Result<()> {
+    /// Returns the path of the new archive (`base.log.1`), if there was
+    /// anything to rotate.
+    fn rotate_by_size(&self) -> io::Result<Option<PathBuf>> {
         // Rotate the *current* file (which may include a time suffix) rather than
         // the base path. This ensures hybrid (Both) rotation behaves sensibly —
         // size-based rotations will operate on the active file (e.g. `base.2026-01-15`)
         // instead of an unrelated `base` path.
-        let max_files = self.trigger.max_files().unwrap_or(5);
-        let current = self.current_file_path();
+        let suffix = self.current_time_suffix();
+        // Triggers without a file limit keep every rotated file, leaving them
+        // to the retention policy and the total size limit
+        let max_files = match self.trigger.max_files() {
+            Some(max_files) => max_files,
+            None => {
+                let archives = self.scan_archives()?;
+                let highest = archives
+                    .iter()
+                    .filter(|archive| archive.suffix == suffix)
+                    .filter_map(|archive| archive.index)
+                    .max();
+                highest.unwrap_or(0) + 1
+            }
+        };
+        let current = self.file_path_for_suffix(&suffix);
+        let indexed = |i: usize| self.archive_path(&suffix, Some(i));
 
-        // Delete the oldest file if it exists (current.<max_files>)
-        let oldest = PathBuf::from(format!("{}.{}", current.display(), max_files));
-        if oldest.exists() {
-            std::fs::remove_file(&oldest)?;
+        // Delete the oldest file if it exists (current.<max_files>, compressed or not)
+        for oldest in archive_variants(&indexed(max_files)) {
+            self.remove_archive(&oldest)?;
         }
 
         // Shift files: current.(N-1) -> current.N, ..., current.1 -> current.2
         for i in (1..max_files).rev() {
-            let from = PathBuf::from(format!("{}.{}", current.display(), i));
-            let to = PathBuf::from(format!("{}.{}", current.display(), i + 1));
-            if from.exists() {
-                std::fs::rename(&from, &to)?;
+            for (from, to) in archive_variants(&indexed(i)).zip(archive_variants(&indexed(i + 1))) {
+                self.rename_archive(&from, &to)?;
             }
         }
 
-        // Copy current file content to current.1 and truncate the current file
-        if current.exists() {
-            let first = PathBuf::from(format!("{}.1", current.display()));
-            std::fs::copy(&current, &first)?;
+        if !current.exists() {
+            return Ok(None);
+        }
 
-            // Truncate the original current file to 0 bytes
-            let file = std::fs::OpenOptions::new()
-                .write(true)
-                .truncate(true)
-                .open(&current)?;
-            file.set_len(0)?;
+        let first = indexed(1);
+        match self.strategy {
+            RotationStrategy::CopyTruncate => {
+                // Copy current file content to current.1 and truncate the current file
+                std::fs::copy(&current, &first)?;
+                self.permissions.apply_to_path(&first)?;
+
+                // Truncate the original current file to 0 bytes
+                let file = std::fs::OpenOptions::new()
+                    .write(true)
+                    .truncate(true)
+                    .open(&current)?;
+                file.set_len(0)?;
+            }
+            RotationStrategy::Rename => {
+                // Atomically move the current file aside, unless the archive
+                // directory is on another file system
+                self.move_file(&current, &first)?;
+            }
         }
 
-        Ok(())
+        Ok(Some(first))
     }
 
-    /// Perform rotation and create a new file.
-    fn rotate(&self) -> io::Result<FileState> {
-        // For size-based rotation, perform the rename chain
-        if self.trigger.has_size_rotation() {
-            // Use base_path for size rotation (not time-suffixed path)
-            self.rotate_by_size()?;
+    /// Find the rotated files of this writer.
+    ///
+    /// Matches size-rotated files (`base.1`), time-suffixed files of past periods
+    /// (`base.2026-01-09`) and their size-rotated siblings (`base.2026-01-09.1`),
+    /// compressed or not, according to the naming scheme. The active file is
+    /// never included.
+    fn scan_archives(&self) -> io::Result<Vec<ArchiveFile>> {
+        let mut archives = Vec::new();
+        let current_suffix = self.current_time_suffix();
+
+        for entry in std::fs::read_dir(self.archive_dir())? {
+            let entry = entry?;
+            let file_name = entry.file_name();
+            let Some((suffix, index, compressed)) = file_name
+                .to_str()
+                .and_then(|name| self.parse_file_name(name))
+            else {
+                continue;
+            };
+
+            if suffix == current_suffix && index.is_none() && !compressed {
+                // The active file
+                continue;
+            }
+
+            archives.push(ArchiveFile {
+                path: entry.path(),
+                suffix,
+                index,
+                compressed,
+            });
         }
 
-        // Open/create the new file
-        let file_path = self.current_file_path();
-        let file = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&file_path)?;
+        Ok(archives)
+    }
 
-        let size = file.metadata().map(|m| m.len()).unwrap_or(0);
+    /// Parse the name of a rotated file into its time suffix, index and whether
+    /// it's compressed.
+    fn parse_file_name(&self, name: &str) -> Option<(String, Option<usize>, bool)> {
+        let (name, compressed) = Compression::strip_extension(name);
+        let (suffix, index) = self
+            .naming
+            .parse(&self.base_name, name, |s| self.is_time_suffix(s))?;
+        Some((suffix, index, compressed))
+    }
 
-        Ok(FileState {
-            file,
-            size,
-            time_suffix: self.current_time_suffix(),
+    /// Check if `suffix` is a valid time suffix for the rotation period.
+    #[cfg(feature = "time")]
+    fn is_time_suffix(&self, suffix: &str) -> bool {
+        self.trigger.period().is_some_and(|period| {
+            period
+                .parse_suffix(suffix, self.offset_at(self.clock.now()))
+                .is_some()
         })
     }
 
-    /// Get or create the current file, rotating if necessary.
-    fn get_or_rotate(&self, buf_len: usize) -> io::Result<Arc<Mutex<Option<FileState>>>> {
-        let mut guard = self.state.lock().unwrap();
+    /// Check if `suffix` is a valid time suffix (always false without time feature).
+    #[cfg(not(feature = "time"))]
+    fn is_time_suffix(&self, _suffix: &str) -> bool {
+        false
+    }
 
-        let needs_rotation = match &*guard {
-            None => {
-                // First time initialization - check if we can use existing file
-                !self.should_use_existing_file()?
+    /// Collect the files of past periods, grouped by the start of their period.
+    #[cfg(feature = "time")]
+    fn past_periods(
+        &self,
+        period: RotationPeriod,
+        now: time::OffsetDateTime,
+    ) -> io::Result<std::collections::BTreeMap<time::OffsetDateTime, Vec<PathBuf>>> {
+        let mut periods = std::collections::BTreeMap::<_, Vec<PathBuf>>::new();
+        let current_suffix = period.suffix_at(now);
+
+        for archive in self.scan_archives()? {
+            if archive.suffix.is_empty() || archive.suffix == current_suffix {
+                continue;
             }
-            Some(state) => self.needs_rotation(state, buf_len),
-        };
-
-        if needs_rotation {
-            // Close current file (drop it)
-            *guard = None;
+            if let Some(start) = period.parse_suffix(&archive.suffix, now.offset()) {
+                periods.entry(start).or_default().push(archive.path);
+            }
+        }
 
-            // Perform rotation and create new file
-            let new_state = self.rotate()?;
-            *guard = Some(new_state);
-        } else if guard.is_none() {
-            // No rotation needed and no current state - open existing file
-            let file_path = self.current_file_path();
-            let file = OpenOptions::new()
-                .create(true)
-                .append(true)
-                .open(&file_path)?;
+        Ok(periods)
+    }
 
-            let size = file.metadata().map(|m| m.len()).unwrap_or(0);
-            let time_suffix = self.current_time_suffix();
+    /// Delete the files of past periods that exceed the retention policy.
+    #[cfg(feature = "time")]
+    fn prune_expired_periods(&self) -> io::Result<()> {
+        if !self.retention.is_enabled() {
+            return Ok(());
+        }
+        let Some(period) = self.trigger.period() else {
+            return Ok(());
+        };
+        if period == RotationPeriod::Never {
+            return Ok(());
+        }
 
-            *guard = Some(FileState {
-                file,
-                size,
-                time_suffix,
+        let now = self.now();
+        let periods = self.past_periods(period, now)?;
+
+        // Newest first; periods starting in the future (clock skew) are kept
+        let past = periods.into_iter().rev().filter(|(start, _)| *start <= now);
+        for (kept, (start, files)) in past.enumerate() {
+            let over_count = self
+                .retention
+                .max_periods
+                .is_some_and(|max_periods| kept >= max_periods);
+            let too_old = self.retention.max_age.is_some_and(|max_age| {
+                period
+                    .period_end(start)
+                    .and_then(|end| end.checked_add(time::Duration::try_from(max_age).ok()?))
+                    .is_some_and(|expiry| expiry <= now)
             });
+
+            if over_count || too_old {
+                for file in files {
+                    self.remove_archive(&file)?;
+                }
+            }
         }
 
-        Ok(Arc::clone(&self.state))
+        Ok(())
     }
-}
-
-impl Write for RotatingWriter {
-    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        let state_arc = self.get_or_rotate(buf.len())?;
-        let mut guard = state_arc.lock().unwrap();
 
-        if let Some(state) = guard.as_mut() {
-            let written = state.file.write(buf)?;
-            state.size += written as u64;
-            Ok(written)
-        } else {
-            Err(io::Error::other("Failed to open log file"))
-        }
+    /// Delete the files of past periods (no-op without time feature).
+    #[cfg(not(feature = "time"))]
+    fn prune_expired_periods(&self) -> io::Result<()> {
+        Ok(())
     }
 
-    fn flush(&mut self) -> io::Result<()> {
+    /// Get the size of the active file.
+    fn active_size(&self) -> u64 {
         let guard = self.state.lock().unwrap();
-        if let Some(state) = guard.as_ref() {
-            // We need interior mutability for flush, so we use a trick:
-            // File::flush takes &mut self, but we can sync_all() on &File
-            state.file.sync_all()
-        } else {
-            Ok(())
-        }
+        guard.as_ref().map_or(0, |state| state.size)
     }
-}
 
-// Implement Send for use with non_blocking
-unsafe impl Send for RotatingWriter {}
+    /// Check if the files may exceed the total size limit, based on the last scan.
+    fn exceeds_total_size(&self, active_size: u64) -> bool {
+        let archive_size = self.archive_size.load(Ordering::Relaxed);
+        self.max_total_size
+            .is_some_and(|max| archive_size > 0 && active_size + archive_size > max)
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::time::{SystemTime, UNIX_EPOCH};
+    /// Delete the oldest rotated files until the active file and all rotated
+    /// files fit within the total size limit.
+    ///
+    /// Time suffixes sort chronologically, and within a period a higher index is
+    /// older, so archives are deleted in that order. The active file is never
+    /// deleted, even if it exceeds the limit on its own.
+    fn enforce_total_size(&self, active_size: u64) -> io::Result<()> {
+        let Some(max_total_size) = self.max_total_size else {
+            return Ok(());
+        };
 
-    fn unique_test_dir(prefix: &str) -> PathBuf {
-        let unique = format!(
-            "{}_{}",
-            std::process::id(),
-            SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap()
-                .as_nanos()
-        );
-        std::env::temp_dir().join(format!("lazydns_log_test_{}_{}", prefix, unique))
-    }
+        let mut archives = Vec::new();
+        for archive in self.scan_archives()? {
+            // Files waiting for compression are about to shrink; they're
+            // counted once compressed, when the limit is enforced again
+            if self
+                .compressor
+                .get()
+                .is_some_and(|compressor| compressor.is_pending(&archive.path))
+            {
+                continue;
+            }
+            match std::fs::metadata(&archive.path) {
+                Ok(metadata) => archives.push((archive, metadata.len())),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e),
+            }
+        }
+        // Newest first, so the oldest can be popped off the end
+        archives.sort_by(|(a, _), (b, _)| {
+            (&b.suffix, a.index.unwrap_or(0)).cmp(&(&a.suffix, b.index.unwrap_or(0)))
+        });
+
+        let mut archive_size: u64 = archives.iter().map(|(_, size)| size).sum();
+        while active_size + archive_size > max_total_size {
+            let Some((oldest, size)) = archives.pop() else {
+                break;
+            };
+            self.remove_archive(&oldest.path)?;
+            archive_size -= size;
+        }
 
-    fn cleanup_dir(dir: &PathBuf) {
-        let _ = std::fs::remove_dir_all(dir);
+        self.archive_size.store(archive_size, Ordering::Relaxed);
+        Ok(())
     }
 
-    #[test]
-    fn test_rotating_writer_creates_file() {
-        let dir = unique_test_dir("create");
-        std::fs::create_dir_all(&dir).unwrap();
+    /// Queue a rotated file for background compression, if enabled, then run
+    /// the hooks for the rotation that produced it, if any.
+    fn compress_archive(&self, path: PathBuf, event: Option<RotationEvent>) {
+        match self.compressor.get() {
+            Some(compressor) => compressor.submit(path, event),
+            None => {
+                if let Some(event) = event {
+                    self.hooks.dispatch(event);
+                }
+            }
+        }
+    }
 
-        let log_path = dir.join("test.log");
-        let mut writer =
-            RotatingWriter::new(&log_path, RotationTrigger::Never).expect("create writer");
+    /// Queue uncompressed archives for compression and remove stale temporary
+    /// files left behind by an interrupted compression.
+    fn compress_leftover_archives(&self) -> io::Result<()> {
+        if self.compressor.get().is_none() {
+            return Ok(());
+        }
 
-        writer.write_all(b"hello world\n").unwrap();
-        writer.flush().unwrap();
+        // Temporary files are named `.<archive>.<ext>.tmp`
+        for entry in std::fs::read_dir(self.archive_dir())? {
+            let entry = entry?;
+            let file_name = entry.file_name();
+            let is_temp = file_name
+                .to_str()
+                .and_then(|name| name.strip_prefix('.')?.strip_suffix(".tmp"))
+                .is_some_and(|name| self.parse_file_name(name).is_some_and(|(_, _, c)| c));
+            if !is_temp {
+                continue;
+            }
+            // Another process is still compressing into it if it's locked
+            let temp = match File::options().write(true).open(entry.path()) {
+                Ok(temp) => temp,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e),
+            };
+            if temp.try_lock().is_ok() {
+                std::fs::remove_file(entry.path())?;
+            }
+        }
 
-        assert!(log_path.exists());
-        let content = std::fs::read_to_string(&log_path).unwrap();
-        assert!(content.contains("hello world"));
+        for archive in self.scan_archives()? {
+            if !archive.compressed {
+                self.compress_archive(archive.path, None);
+            }
+        }
 
-        cleanup_dir(&dir);
+        Ok(())
     }
 
-    #[test]
-    fn test_rotating_writer_creates_parent_dir() {
-        // Don't pre-create nested dirs; writer should create them automatically
-        let dir = unique_test_dir("parent_create");
-        let nested = dir.join("nested/inner");
-        let log_path = nested.join("test.log");
+    /// Move the rotated files left in the log directory to the archive
+    /// directory, if there is one.
+    fn move_leftover_archives(&self) -> io::Result<()> {
+        if self.archive_dir.is_none() {
+            return Ok(());
+        }
 
-        // Parent does not exist yet
-        assert!(!nested.exists());
+        let current_suffix = self.current_time_suffix();
+        for entry in std::fs::read_dir(self.log_dir())? {
+            let entry = entry?;
+            if !entry.file_type()?.is_file() {
+                // Skip the symlink to the active file
+                continue;
+            }
+            let file_name = entry.file_name();
+            let Some((suffix, index, compressed)) = file_name
+                .to_str()
+                .and_then(|name| self.parse_file_name(name))
+            else {
+                continue;
+            };
+            if index.is_none() && !compressed && (suffix.is_empty() || suffix == current_suffix) {
+                // The active file, or the base file of another trigger
+                continue;
+            }
 
-        // Creating writer should create parent directories
-        let mut writer =
-            RotatingWriter::new(&log_path, RotationTrigger::Never).expect("create writer");
+            self.move_file(&entry.path(), &self.archive_dir().join(&file_name))?;
+        }
 
-        writer.write_all(b"hello parent\n").unwrap();
-        writer.flush().unwrap();
+        Ok(())
+    }
 
-        assert!(log_path.exists(), "Log file should have been created");
-        let content = std::fs::read_to_string(&log_path).unwrap();
-        assert!(content.contains("hello parent"));
+    /// Move a file, copying it and deleting the original when the destination
+    /// is on another file system.
+    fn move_file(&self, from: &Path, to: &Path) -> io::Result<()> {
+        match std::fs::rename(from, to) {
+            Err(e) if e.kind() == io::ErrorKind::CrossesDevices => {
+                std::fs::copy(from, to)?;
+                self.permissions.apply_to_path(to)?;
+                std::fs::remove_file(from)
+            }
+            result => result,
+        }
+    }
 
-        // Parent directories should exist now
-        assert!(
-            nested.exists(),
-            "Parent directories should have been created"
-        );
+    /// Rename a rotated file; a missing source is not an error.
+    fn rename_archive(&self, from: &Path, to: &Path) -> io::Result<()> {
+        let result = match self.compressor.get() {
+            Some(compressor) => compressor.rename(from, to),
+            None => std::fs::rename(from, to),
+        };
+        match result {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+            result => result,
+        }
+    }
 
-        cleanup_dir(&dir);
+    /// Delete a rotated file; a missing file is not an error.
+    fn remove_archive(&self, path: &Path) -> io::Result<()> {
+        let result = match self.compressor.get() {
+            Some(compressor) => compressor.remove(path),
+            None => std::fs::remove_file(path),
+        };
+        match result {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+            result => result,
+        }
     }
 
-    #[test]
-    fn test_rotating_writer_size_rotation() {
-        let dir = unique_test_dir("size");
+    /// Perform rotation and create a new file.
+    ///
+    /// Returns the new file, and the path of the archive holding the closed
+    /// file's content (if any).
+    fn rotate(
+        &self,
+        reason: RotationReason,
+        previous_suffix: &str,
+    ) -> io::Result<(FileState, Option<PathBuf>)> {
+        // Recreate the archive directory if it was removed
+        if let Some(archive_dir) = &self.archive_dir {
+            self.permissions.create_dir_all(archive_dir)?;
+        }
+
+        let closed = match reason {
+            // A new period starts a new file; the previous one stays in place
+            // unless rotated files are kept elsewhere
+            RotationReason::Time => {
+                let previous = self.file_path_for_suffix(previous_suffix);
+                if !previous.exists() || previous_suffix == self.current_time_suffix() {
+                    // Nothing to close, e.g. the clock was set back: the file
+                    // is resumed with a fresh end of period
+                    None
+                } else if self.archive_dir.is_some() {
+                    let archived = self.archive_path(previous_suffix, None);
+                    self.move_file(&previous, &archived)?;
+                    Some(archived)
+                } else {
+                    Some(previous)
+                }
+            }
+            // Move the active file down the rename chain
+            RotationReason::Size | RotationReason::Lines | RotationReason::Manual => {
+                self.rotate_by_size()?
+            }
+        };
+
+        // Open/create the new file
+        Ok((self.open_current(closed.as_deref())?, closed))
+    }
+
+    /// Open the current period's file for appending, creating it if necessary.
+    ///
+    /// An existing file is resumed as is: its size counts toward size-based
+    /// rotation, and its rotated siblings keep their indices. An empty file
+    /// starts with the header, if any, naming the `previous` file. The header
+    /// is never counted as a record, including in a resumed file.
+    fn open_current(&self, previous: Option<&Path>) -> io::Result<FileState> {
+        let (time_suffix, rotate_at) = self.current_period();
+        // Timestamps switch to a new local offset along with the files
+        #[cfg(feature = "time")]
+        self.timezone.set_offset(self.now().offset());
+        let file_path = self.file_path_for_suffix(&time_suffix);
+        let file = self
+            .permissions
+            .open_options()
+            .create(true)
+            .append(true)
+            .open(&file_path)?;
+        self.permissions.apply_to_file(&file)?;
+
+        let mut size = file.metadata().map(|m| m.len()).unwrap_or(0);
+        let header_len = match &self.header {
+            Some(header) if size == 0 => {
+                let header = header.render(&file_path, previous);
+                (&file).write_all(&header)?;
+                size = header.len() as u64;
+                size
+            }
+            _ => self.find_header(&file_path)?,
+        };
+        let lines = if self.trigger.max_lines().is_some() {
+            count_lines(&file_path, header_len)?
+        } else {
+            0
+        };
+
+        // A stale link is preferable to failing the write
+        if let Err(e) = self.update_symlink(&file_path) {
+            eprintln!("lazylog: failed to update log file symlink: {}", e);
+        }
+
+        Ok(FileState {
+            file: BufWriter::with_capacity(self.buffer_size, file),
+            size,
+            lines,
+            time_suffix,
+            rotate_at,
+            checked_at: Instant::now(),
+            synced_at: Instant::now(),
+            record_len: 0,
+            truncating: false,
+            header_len,
+        })
+    }
+
+    /// Get the length of the header at the start of the file at `path`, or 0
+    /// if it doesn't start with one.
+    fn find_header(&self, path: &Path) -> io::Result<u64> {
+        let Some(header) = &self.header else {
+            return Ok(0);
+        };
+        match File::open(path) {
+            Ok(file) => Ok(header.find_in(file)?.unwrap_or(0)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(0),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Atomically point the symlink, if any, at the active file `target`.
+    #[cfg(unix)]
+    fn update_symlink(&self, target: &Path) -> io::Result<()> {
+        let Some(link) = &self.symlink else {
+            return Ok(());
+        };
+        if link == target {
+            // Size-only rotation with the link at the base path
+            return Ok(());
+        }
+
+        // Never replace a regular file, such as a log file from before the
+        // symlink was configured
+        if let Ok(metadata) = std::fs::symlink_metadata(link)
+            && !metadata.file_type().is_symlink()
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("{} exists and is not a symlink", link.display()),
+            ));
+        }
+
+        // Link by file name when side by side, so the directory can be moved
+        let link_target = match target.file_name() {
+            Some(name) if link.parent() == target.parent() => PathBuf::from(name),
+            _ => std::path::absolute(target)?,
+        };
+
+        // Create the new link under a temporary name and rename it over the old one
+        let link_name = link
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let temp_link = link.with_file_name(format!(".{}.link.tmp", link_name));
+        match std::fs::remove_file(&temp_link) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+            _ => {}
+        }
+        std::os::unix::fs::symlink(&link_target, &temp_link)?;
+        std::fs::rename(&temp_link, link)
+    }
+
+    /// Symlinks are only supported on Unix.
+    #[cfg(not(unix))]
+    fn update_symlink(&self, _target: &Path) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Rotate the active file, with the state lock held.
+    fn rotate_locked(
+        &self,
+        state: &mut Option<FileState>,
+        reason: RotationReason,
+    ) -> io::Result<()> {
+        // Write out buffered records before the file is moved aside
+        if let Some(previous) = state.as_mut() {
+            previous.file.flush()?;
+        }
+
+        // Close current file (drop it)
+        let (previous_suffix, bytes) = match state.take() {
+            Some(previous) => (previous.time_suffix, previous.size),
+            None => (self.current_time_suffix(), 0),
+        };
+
+        // Perform rotation and create new file
+        let (new_state, closed) = self.rotate(reason, &previous_suffix)?;
+        let opened = self.file_path_for_suffix(&new_state.time_suffix);
+        let period_changed = previous_suffix != new_state.time_suffix;
+        *state = Some(new_state);
+
+        // Archive the closed file, then let the hooks know
+        if let Some(closed) = closed {
+            let event = RotationEvent {
+                closed: closed.clone(),
+                opened,
+                reason,
+                bytes,
+            };
+            self.compress_archive(closed, Some(event));
+        }
+
+        // A new period started: the oldest period may have expired
+        if period_changed {
+            self.prune_expired_periods()?;
+        }
+
+        // Rotation added an archive, which may push the files over the limit
+        let active_size = state.as_ref().map_or(0, |state| state.size);
+        self.enforce_total_size(active_size)
+    }
+
+    /// Rotate the active file unless it has no records, with the state lock held.
+    fn rotate_manually(&self, state: &mut Option<FileState>) -> io::Result<()> {
+        if state.as_ref().is_some_and(FileState::has_records) {
+            self.rotate_locked(state, RotationReason::Manual)?;
+        }
+        Ok(())
+    }
+
+    /// Get or create the current file, rotating if necessary.
+    fn prepare(&self, guard: &mut Option<FileState>, buf_len: usize) -> io::Result<()> {
+        if guard.as_mut().is_some_and(|state| self.was_replaced(state)) {
+            self.reopen_locked(guard)?;
+        }
+
+        let needs_rotation = match &*guard {
+            // First time initialization: resume the current file, never rotate
+            // just because the process restarted
+            None => None,
+            Some(state) => self.needs_rotation(state, buf_len),
+        };
+
+        if let Some(reason) = needs_rotation {
+            // A record cut short ends in its own file rather than in the next
+            if let Some(state) = guard.as_mut()
+                && state.truncating
+            {
+                Self::end_truncated(state)?;
+            }
+            self.rotate_locked(guard, reason)?;
+        } else if guard.is_none() {
+            // No rotation needed and no current state - open existing file
+            *guard = Some(self.open_current(None)?);
+        } else if let Some(state) = guard.as_ref()
+            && self.exceeds_total_size(state.size + buf_len as u64)
+        {
+            // The active file grew into the budget of the rotated files
+            self.enforce_total_size(state.size + buf_len as u64)?;
+        }
+
+        Ok(())
+    }
+
+    /// Write a record to the active file, with the state lock held.
+    fn write_locked(&self, state: &mut Option<FileState>, buf: &[u8]) -> io::Result<usize> {
+        let _lock = self.lock_processes()?;
+        if self.lock_file.is_some() {
+            self.catch_up(state)?;
+        }
+        self.prepare(state, buf.len())?;
+
+        let Some(state) = state.as_mut() else {
+            return Err(io::Error::other("Failed to open log file"));
+        };
+        match (self.oversized, self.trigger.max_size()) {
+            (OversizedRecord::Truncate, Some(max_size)) => {
+                Self::write_truncated(state, buf, max_size)?;
+                Ok(buf.len())
+            }
+            _ => {
+                let written = state.file.write(buf)?;
+                state.size += written as u64;
+                Self::track_records(state, &buf[..written]);
+                Ok(written)
+            }
+        }
+    }
+
+    /// Keep track of complete records and where the current record started in `written`.
+    fn track_records(state: &mut FileState, written: &[u8]) {
+        state.lines += written.iter().filter(|&&b| b == b'\n').count() as u64;
+        match written.iter().rposition(|&b| b == b'\n') {
+            Some(newline) => state.record_len = (written.len() - newline - 1) as u64,
+            None => state.record_len += written.len() as u64,
+        }
+    }
+
+    /// Write records, cutting each one to at most `max_size` bytes including
+    /// the truncation marker and newline.
+    fn write_truncated(state: &mut FileState, buf: &[u8], max_size: u64) -> io::Result<()> {
+        let write = |state: &mut FileState, bytes: &[u8]| -> io::Result<()> {
+            state.file.write_all(bytes)?;
+            state.size += bytes.len() as u64;
+            state.lines += bytes.iter().filter(|&&b| b == b'\n').count() as u64;
+            Ok(())
+        };
+
+        let mut rest = buf;
+        while !rest.is_empty() {
+            let (mut chunk, ends_record) = match rest.iter().position(|&b| b == b'\n') {
+                Some(newline) => (&rest[..=newline], true),
+                None => (rest, false),
+            };
+            rest = &rest[chunk.len()..];
+
+            if !state.truncating && state.record_len + chunk.len() as u64 <= max_size {
+                write(state, chunk)?;
+                state.record_len = if ends_record {
+                    0
+                } else {
+                    state.record_len + chunk.len() as u64
+                };
+                continue;
+            }
+
+            if !state.truncating {
+                // Keep what fits along with the marker and newline
+                let room = max_size.saturating_sub(OversizedRecord::MARKER.len() as u64 + 1);
+                let keep = room
+                    .saturating_sub(state.record_len)
+                    .min(chunk.len() as u64);
+                write(state, &chunk[..keep as usize])?;
+                state.record_len += keep;
+                state.truncating = true;
+                chunk = &chunk[keep as usize..];
+            }
+            if ends_record {
+                Self::end_truncated(state)?;
+                continue;
+            }
+
+            // Without a newline the output may not be line based at all, so the
+            // rest of a cut record is only dropped until the record reaches
+            // twice the size limit, and what follows starts a new record
+            let dropped = max_size
+                .max(1)
+                .saturating_mul(2)
+                .saturating_sub(state.record_len);
+            if (chunk.len() as u64) < dropped {
+                state.record_len += chunk.len() as u64;
+            } else {
+                Self::end_truncated(state)?;
+                rest = &chunk[dropped as usize..];
+            }
+        }
+        Ok(())
+    }
+
+    /// End a record cut short by [`OversizedRecord::Truncate`] with the marker.
+    fn end_truncated(state: &mut FileState) -> io::Result<()> {
+        state.file.write_all(OversizedRecord::MARKER)?;
+        state.file.write_all(b"\n")?;
+        state.size += OversizedRecord::MARKER.len() as u64 + 1;
+        state.lines += 1;
+        state.record_len = 0;
+        state.truncating = false;
+        Ok(())
+    }
+
+    /// Flush the active file according to the durability mode, with the state lock held.
+    fn flush_locked(&self, state: &mut FileState) -> io::Result<()> {
+        let sync = match self.durability {
+            Durability::None => return Ok(()),
+            Durability::Flush => false,
+            Durability::FsyncOnFlush => true,
+            Durability::FsyncInterval(interval) => state.synced_at.elapsed() >= interval,
+        };
+
+        state.file.flush()?;
+        if sync {
+            state.file.get_ref().sync_data()?;
+            state.synced_at = Instant::now();
+        }
+        Ok(())
+    }
+
+    /// Close the active file, with the state lock held.
+    ///
+    /// Buffered records are written out first, and the file is synced if the
+    /// durability mode asks for it. As the file is closed regardless, records
+    /// that can't be written are handled by the write failure policy.
+    fn close_locked(&self, state: &mut Option<FileState>) {
+        let Some(mut previous) = state.take() else {
+            return;
+        };
+        let sync = match self.durability {
+            Durability::None | Durability::Flush => false,
+            Durability::FsyncOnFlush | Durability::FsyncInterval(_) => true,
+        };
+        let result = previous.file.flush().and_then(|()| {
+            if sync {
+                previous.file.get_ref().sync_data()
+            } else {
+                Ok(())
+            }
+        });
+        if let Err(e) = result {
+            let (_, buffered) = previous.file.into_parts();
+            self.failures
+                .failed(&self.base_path, &e, &buffered.unwrap_or_default());
+        }
+    }
+
+    /// Take the lock shared by all processes writing to the log files, in
+    /// multi-process mode. The lock is released when the guard is dropped.
+    fn lock_processes(&self) -> io::Result<Option<ProcessLock<'_>>> {
+        self.lock_file
+            .as_ref()
+            .map(ProcessLock::acquire)
+            .transpose()
+    }
+
+    /// Catch up with rotations done by other processes, with the process lock held.
+    ///
+    /// Reopens the active file if another process moved it aside or started a
+    /// new period, and takes the file size from the file itself, so that the
+    /// rotation decision is based on what all processes wrote.
+    fn catch_up(&self, state: &mut Option<FileState>) -> io::Result<()> {
+        let Some(current) = state.as_mut() else {
+            return Ok(());
+        };
+
+        let new_period_started = current.period_ended(self.clock.now()) && {
+            let suffix = self.current_time_suffix();
+            suffix != current.time_suffix && self.file_path_for_suffix(&suffix).exists()
+        };
+        if new_period_started || self.is_replaced(current) {
+            return self.reopen_locked(state);
+        }
+
+        let size = current.file.get_ref().metadata()?.len();
+        let path = self.file_path_for_suffix(&current.time_suffix);
+        if size < current.size {
+            // Truncated by another process, which started it with its header
+            current.header_len = self.find_header(&path)?;
+        }
+        if self.trigger.max_lines().is_some() && size != current.size {
+            current.lines = if size < current.size {
+                count_lines(&path, current.header_len)?
+            } else {
+                current.lines + count_lines(&path, current.size)?
+            };
+        }
+        current.size = size;
+        Ok(())
+    }
+
+    /// Check if the active file was moved or deleted since it was opened,
+    /// at most once per check interval.
+    fn was_replaced(&self, state: &mut FileState) -> bool {
+        if state.checked_at.elapsed() < self.reopen_check {
+            return false;
+        }
+        state.checked_at = Instant::now();
+        self.is_replaced(state)
+    }
+
+    /// Check if the active file is no longer at its path.
+    fn is_replaced(&self, state: &FileState) -> bool {
+        let path = self.file_path_for_suffix(&state.time_suffix);
+        match (std::fs::metadata(&path), state.file.get_ref().metadata()) {
+            (Ok(on_disk), Ok(open)) => !same_file(&on_disk, &open),
+            (Err(e), _) => e.kind() == io::ErrorKind::NotFound,
+            (_, Err(_)) => false,
+        }
+    }
+
+    /// Close the active file and open it again by name, with the state lock held.
+    fn reopen_locked(&self, state: &mut Option<FileState>) -> io::Result<()> {
+        // Close the old handle first
+        self.close_locked(state);
+
+        // Recreate the directory if it was removed along with the file
+        if let Some(parent) = self.base_path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            self.permissions.create_dir_all(parent)?;
+        }
+
+        *state = Some(self.open_current(None)?);
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for RotatingWriter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RotatingWriter")
+            .field("path", &self.inner.base_path)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Write for RotatingWriter {
+    /// Write a record, rotating first if needed.
+    ///
+    /// Failures are handled by the configured [`WriteFailurePolicy`](crate::WriteFailurePolicy)
+    /// rather than returned, so a record is never silently lost in the
+    /// background writer.
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let inner = &*self.inner;
+        let mut guard = inner.state.lock().unwrap();
+        let result = match inner.failures.next_attempt() {
+            Attempt::Write => inner.write_locked(&mut guard, buf),
+            Attempt::Reopen => inner
+                .reopen_locked(&mut guard)
+                .and_then(|()| inner.write_locked(&mut guard, buf)),
+            Attempt::Skip => {
+                inner.failures.skipped();
+                return Ok(buf.len());
+            }
+        };
+
+        match result {
+            Ok(written) => {
+                inner.failures.succeeded(&inner.base_path);
+                Ok(written)
+            }
+            Err(e) => {
+                inner.failures.failed(&inner.base_path, &e, buf);
+                Ok(buf.len())
+            }
+        }
+    }
+
+    /// Flush according to the configured [`Durability`].
+    ///
+    /// As with writes, failures are handled by the write failure policy: the
+    /// file is closed, the records it couldn't take count as dropped, and the
+    /// next write opens it again.
+    fn flush(&mut self) -> io::Result<()> {
+        let inner = &*self.inner;
+        let mut guard = inner.state.lock().unwrap();
+        if let Some(state) = guard.as_mut()
+            && inner.flush_locked(state).is_err()
+        {
+            inner.close_locked(&mut guard);
+        }
+        Ok(())
+    }
+}
+
+/// Count the newlines in the file at `path` after the first `offset` bytes.
+fn count_lines(path: &Path, offset: u64) -> io::Result<u64> {
+    use std::io::{BufRead, BufReader, Seek, SeekFrom};
+
+    let mut reader = match File::open(path) {
+        Ok(file) => BufReader::new(file),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(e),
+    };
+    reader.seek(SeekFrom::Start(offset))?;
+
+    let mut lines = 0;
+    loop {
+        let chunk = reader.fill_buf()?;
+        if chunk.is_empty() {
+            return Ok(lines);
+        }
+        lines += chunk.iter().filter(|&&b| b == b'\n').count() as u64;
+        let len = chunk.len();
+        reader.consume(len);
+    }
+}
+
+/// Holds the lock on the lock file shared by processes until dropped.
+pub(crate) struct ProcessLock<'a>(&'a File);
+
+impl<'a> ProcessLock<'a> {
+    /// Wait for the lock on `file`.
+    pub(crate) fn acquire(file: &'a File) -> io::Result<Self> {
+        file.lock()?;
+        Ok(Self(file))
+    }
+}
+
+impl Drop for ProcessLock<'_> {
+    fn drop(&mut self) {
+        let _ = self.0.unlock();
+    }
+}
+
+/// Check if two files' metadata refer to the same file.
+#[cfg(unix)]
+pub(crate) fn same_file(a: &std::fs::Metadata, b: &std::fs::Metadata) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    a.dev() == b.dev() && a.ino() == b.ino()
+}
+
+/// Check if two files' metadata refer to the same file. Without file
+/// identities on this platform, only deleted files are detected.
+#[cfg(not(unix))]
+pub(crate) fn same_file(_a: &std::fs::Metadata, _b: &std::fs::Metadata) -> bool {
+    true
+}
+
+/// All names a rotated file may have: plain and with each compression extension.
+fn archive_variants(path: &Path) -> impl Iterator<Item = PathBuf> + '_ {
+    std::iter::once(path.to_path_buf()).chain(
+        Compression::EXTENSIONS
+            .iter()
+            .map(move |ext| PathBuf::from(format!("{}.{}", path.display(), ext))),
+    )
+}
+
+// Implement Send for use with non_blocking
+unsafe impl Send for RotatingWriter {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn unique_test_dir(prefix: &str) -> PathBuf {
+        let unique = format!(
+            "{}_{}",
+            std::process::id(),
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        );
+        std::env::temp_dir().join(format!("lazydns_log_test_{}_{}", prefix, unique))
+    }
+
+    fn cleanup_dir(dir: &PathBuf) {
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_rotating_writer_creates_file() {
+        let dir = unique_test_dir("create");
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let log_path = dir.join("test.log");
+        let mut writer =
+            RotatingWriter::new(&log_path, RotationTrigger::Never).expect("create writer");
+
+        writer.write_all(b"hello world\n").unwrap();
+        writer.flush().unwrap();
+
+        assert!(log_path.exists());
+        let content = std::fs::read_to_string(&log_path).unwrap();
+        assert!(content.contains("hello world"));
+
+        cleanup_dir(&dir);
+    }
+
+    #[test]
+    fn test_rotating_writer_creates_parent_dir() {
+        // Don't pre-create nested dirs; writer should create them automatically
+        let dir = unique_test_dir("parent_create");
+        let nested = dir.join("nested/inner");
+        let log_path = nested.join("test.log");
+
+        // Parent does not exist yet
+        assert!(!nested.exists());
+
+        // Creating writer should create parent directories
+        let mut writer =
+            RotatingWriter::new(&log_path, RotationTrigger::Never).expect("create writer");
+
+        writer.write_all(b"hello parent\n").unwrap();
+        writer.flush().unwrap();
+
+        assert!(log_path.exists(), "Log file should have been created");
+        let content = std::fs::read_to_string(&log_path).unwrap();
+        assert!(content.contains("hello parent"));
+
+        // Parent directories should exist now
+        assert!(
+            nested.exists(),
+            "Parent directories should have been created"
+        );
+
+        cleanup_dir(&dir);
+    }
+
+    #[test]
+    fn test_rotating_writer_size_rotation() {
+        let dir = unique_test_dir("size");
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let log_path = dir.join("test.log");
+        // Very small max_size to trigger rotation quickly
+        let mut writer =
+            RotatingWriter::new(&log_path, RotationTrigger::size(50, 3)).expect("create writer");
+
+        // Write enough to trigger rotation
+        for i in 0..5 {
+            writer
+                .write_all(format!("line {} - some padding text here\n", i).as_bytes())
+                .unwrap();
+        }
+        writer.flush().unwrap();
+
+        // Check that rotated files exist
+        assert!(log_path.exists(), "base log file should exist");
+        let rotated_1 = dir.join("test.log.1");
+        assert!(rotated_1.exists(), "test.log.1 should exist");
+
+        cleanup_dir(&dir);
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn test_rotating_writer_time_suffix() {
+        let dir = unique_test_dir("time");
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let log_path = dir.join("test.log");
+        let mut writer = RotatingWriter::new(
+            &log_path,
+            RotationTrigger::Time {
+                period: RotationPeriod::Daily,
+            },
+        )
+        .expect("create writer");
+
+        writer.write_all(b"hello\n").unwrap();
+        writer.flush().unwrap();
+
+        // Find the time-suffixed file
+        let entries: Vec<_> = std::fs::read_dir(&dir)
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .collect();
+
+        assert!(!entries.is_empty(), "should have created a log file");
+
+        // Check that filename has date suffix
+        let first = &entries[0];
+        let name = first.file_name().to_string_lossy().to_string();
+        assert!(
+            name.starts_with("test.log."),
+            "filename should have date suffix"
+        );
+        assert!(name.contains('-'), "filename should contain date separator");
+
+        cleanup_dir(&dir);
+    }
+
+    #[test]
+    fn test_rotating_writer_reuse_existing_file() {
+        let dir = unique_test_dir("reuse");
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let log_path = dir.join("test.log");
+
+        // Create a file with some content that's under the size limit
+        {
+            let mut file = std::fs::File::create(&log_path).unwrap();
+            file.write_all(b"existing content\n").unwrap();
+        }
+
+        // Create writer with size limit larger than existing content
+        let mut writer =
+            RotatingWriter::new(&log_path, RotationTrigger::size(100, 5)).expect("create writer");
+
+        // Write additional content
+        writer.write_all(b"new content\n").unwrap();
+        writer.flush().unwrap();
+
+        // Check that the file still contains both old and new content
+        let content = std::fs::read_to_string(&log_path).unwrap();
+        assert!(content.contains("existing content"));
+        assert!(content.contains("new content"));
+
+        // Check that no rotated files were created
+        let rotated_1 = dir.join("test.log.1");
+        assert!(!rotated_1.exists(), "Should not have created rotated file");
+
+        cleanup_dir(&dir);
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn test_rotating_writer_hybrid() {
+        let dir = unique_test_dir("hybrid");
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let log_path = dir.join("test.log");
+        let mut writer = RotatingWriter::new(
+            &log_path,
+            RotationTrigger::both(RotationPeriod::Daily, 50, 3),
+        )
+        .expect("create writer");
+
+        // Write enough to trigger size rotation
+        for i in 0..5 {
+            writer
+                .write_all(format!("line {} padding\n", i).as_bytes())
+                .unwrap();
+        }
+        writer.flush().unwrap();
+
+        // Should have created files
+        let entries: Vec<_> = std::fs::read_dir(&dir)
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .collect();
+
+        assert!(!entries.is_empty(), "should have created log files");
+
+        // Find the active time-suffixed file (e.g., test.log.2026-01-15)
+        // Choose the suffixed name whose last segment contains a '-' (date),
+        // which distinguishes it from rotated numeric suffixes like `.1`.
+        let active = entries
+            .iter()
+            .map(|e| e.file_name().to_string_lossy().to_string())
+            .find(|name| {
+                name.starts_with("test.log.")
+                    && name
+                        .rsplit('.')
+                        .next()
+                        .map(|s| s.contains('-'))
+                        .unwrap_or(false)
+            })
+            .expect("active time-suffixed file should exist");
+
+        let rotated_name = format!("{}.1", active);
+        let rotated_path = dir.join(&rotated_name);
+        assert!(
+            rotated_path.exists(),
+            "rotated file {} should exist",
+            rotated_name
+        );
+
+        cleanup_dir(&dir);
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn test_rotating_writer_prunes_expired_periods() {
+        let dir = unique_test_dir("retention");
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let log_path = dir.join("test.log");
+        let old_files = [
+            "test.log.2000-01-01",
+            "test.log.2000-01-01.1",
+            "test.log.2000-01-02",
+            "test.log.2000-01-03",
+            "test.log.2000-01-03.1",
+        ];
+        for name in old_files {
+            std::fs::write(dir.join(name), b"old\n").unwrap();
+        }
+        std::fs::write(dir.join("test.log.unrelated"), b"keep\n").unwrap();
+
+        let config = FileLogConfig::new(&log_path)
+            .with_rotation_trigger(RotationTrigger::both(RotationPeriod::Daily, 1024, 3))
+            .with_retention(Retention::periods(2));
+        let mut writer = RotatingWriter::from_config(&config).expect("create writer");
+        writer.write_all(b"hello\n").unwrap();
+
+        assert!(!dir.join("test.log.2000-01-01").exists());
+        assert!(!dir.join("test.log.2000-01-01.1").exists());
+        assert!(dir.join("test.log.2000-01-02").exists());
+        assert!(dir.join("test.log.2000-01-03").exists());
+        assert!(dir.join("test.log.2000-01-03.1").exists());
+        assert!(dir.join("test.log.unrelated").exists());
+
+        cleanup_dir(&dir);
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn test_rotating_writer_prunes_by_age() {
+        let dir = unique_test_dir("retention_age");
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let log_path = dir.join("test.log");
+        let now = time::OffsetDateTime::now_utc();
+        let recent = RotationPeriod::Daily.suffix_at(now - time::Duration::days(1));
+        std::fs::write(dir.join(format!("test.log.{}", recent)), b"recent\n").unwrap();
+        std::fs::write(dir.join("test.log.2000-01-01"), b"old\n").unwrap();
+
+        let config = FileLogConfig::new(&log_path)
+            .with_rotation_trigger(RotationTrigger::time(RotationPeriod::Daily))
+            .with_retention(Retention::max_age(std::time::Duration::from_secs(
+                7 * 24 * 60 * 60,
+            )));
+        let _writer = RotatingWriter::from_config(&config).expect("create writer");
+
+        assert!(!dir.join("test.log.2000-01-01").exists());
+        assert!(dir.join(format!("test.log.{}", recent)).exists());
+
+        cleanup_dir(&dir);
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn test_rotating_writer_compresses_rotated_files() {
+        use std::io::Read;
+
+        let dir = unique_test_dir("compress");
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let log_path = dir.join("test.log");
+        let config = FileLogConfig::new(&log_path)
+            .with_rotation_trigger(RotationTrigger::size(50, 3))
+            .with_compression(Compression::Gzip);
+        let mut writer = RotatingWriter::from_config(&config).expect("create writer");
+
+        for i in 0..4 {
+            writer
+                .write_all(format!("line {} - some padding text here\n", i).as_bytes())
+                .unwrap();
+        }
+        // Dropping the writer waits for pending compressions
+        drop(writer);
+
+        assert!(log_path.exists());
+        for i in 1..=3 {
+            assert!(
+                dir.join(format!("test.log.{}.gz", i)).exists(),
+                "test.log.{}.gz should exist",
+                i
+            );
+            assert!(!dir.join(format!("test.log.{}", i)).exists());
+        }
+
+        let mut content = String::new();
+        flate2::read::GzDecoder::new(std::fs::File::open(dir.join("test.log.1.gz")).unwrap())
+            .read_to_string(&mut content)
+            .unwrap();
+        assert_eq!(content, "line 2 - some padding text here\n");
+
+        cleanup_dir(&dir);
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn test_rotating_writer_total_size_with_compression() {
+        let dir = unique_test_dir("total_size_compress");
+        std::fs::create_dir_all(&dir).unwrap();
+
+        // Left uncompressed: one shrinks to almost nothing, the other doesn't
+        let log_path = dir.join("test.log");
+        std::fs::write(dir.join("test.log.1"), vec![b'a'; 8000]).unwrap();
+        let mut seed = 0x2545_f491_u32;
+        let noise: Vec<u8> = (0..2500)
+            .map(|_| {
+                seed ^= seed << 13;
+                seed ^= seed >> 17;
+                seed ^= seed << 5;
+                seed as u8
+            })
+            .collect();
+        std::fs::write(dir.join("test.log.2"), noise).unwrap();
+        std::fs::write(dir.join("test.log.3.gz"), vec![0; 1000]).unwrap();
+
+        let config = FileLogConfig::new(&log_path)
+            .with_rotation_trigger(RotationTrigger::size(1024, 5))
+            .with_compression(Compression::Gzip)
+            .with_max_total_size(3000);
+        let writer = RotatingWriter::from_config(&config).expect("create writer");
+
+        // Counted at their uncompressed size, the leftovers would have pushed
+        // everything out; compressed, only the oldest archive has to go
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while dir.join("test.log.1").exists()
+            || dir.join("test.log.2").exists()
+            || dir.join("test.log.3.gz").exists()
+        {
+            assert!(Instant::now() < deadline, "archives not compressed in time");
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert!(dir.join("test.log.1.gz").exists());
+        assert!(dir.join("test.log.2.gz").exists());
+
+        drop(writer);
+        cleanup_dir(&dir);
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn test_rotating_writer_compresses_leftover_archives() {
+        let dir = unique_test_dir("compress_leftover");
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let log_path = dir.join("test.log");
+        std::fs::write(&log_path, b"active\n").unwrap();
+        std::fs::write(dir.join("test.log.1"), b"leftover\n").unwrap();
+        std::fs::write(dir.join(".test.log.2.gz.tmp"), b"partial").unwrap();
+        // Still being written by another process
+        let in_progress = File::create(dir.join(".test.log.3.gz.tmp")).unwrap();
+        in_progress.lock().unwrap();
+
+        let config = FileLogConfig::new(&log_path)
+            .with_rotation_trigger(RotationTrigger::size(1024, 3))
+            .with_compression(Compression::Gzip);
+        drop(RotatingWriter::from_config(&config).expect("create writer"));
+
+        assert!(dir.join("test.log.1.gz").exists());
+        assert!(!dir.join("test.log.1").exists());
+        assert!(!dir.join(".test.log.2.gz.tmp").exists());
+        assert!(dir.join(".test.log.3.gz.tmp").exists());
+
+        cleanup_dir(&dir);
+    }
+
+    #[test]
+    fn test_rotating_writer_shifts_compressed_files() {
+        let dir = unique_test_dir("compress_shift");
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let log_path = dir.join("test.log");
+        std::fs::write(&log_path, b"0123456789\n").unwrap();
+        std::fs::write(dir.join("test.log.1.gz"), b"one").unwrap();
+        std::fs::write(dir.join("test.log.2.zst"), b"two").unwrap();
+
+        let mut writer =
+            RotatingWriter::new(&log_path, RotationTrigger::size(16, 2)).expect("create writer");
+        writer.write_all(b"0123456789\n").unwrap();
+
+        assert!(dir.join("test.log.1").exists());
+        assert_eq!(std::fs::read(dir.join("test.log.2.gz")).unwrap(), b"one");
+        assert!(!dir.join("test.log.2.zst").exists());
+
+        cleanup_dir(&dir);
+    }
+
+    #[cfg(not(feature = "zstd"))]
+    #[test]
+    fn test_rotating_writer_rejects_unsupported_compression() {
+        let dir = unique_test_dir("compress_unsupported");
+        let config = FileLogConfig::new(dir.join("test.log")).with_compression(Compression::Zstd);
+        assert!(RotatingWriter::from_config(&config).is_err());
+        cleanup_dir(&dir);
+    }
+
+    #[test]
+    fn test_rotating_writer_rename_strategy() {
+        let dir = unique_test_dir("rename");
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let log_path = dir.join("test.log");
+        std::fs::write(&log_path, b"first line that fills the file\n").unwrap();
+        let rotated = dir.join("test.log.1");
+
+        let config = FileLogConfig::new(&log_path)
+            .with_rotation_trigger(RotationTrigger::size(40, 3))
+            .with_strategy(RotationStrategy::Rename);
+        let mut writer = RotatingWriter::from_config(&config).expect("create writer");
+
+        // Keep a handle to the original file to verify it was moved, not copied
+        let original = std::fs::File::open(&log_path).unwrap();
+        writer.write_all(b"second line\n").unwrap();
+        writer.flush().unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(&rotated).unwrap(),
+            "first line that fills the file\n"
+        );
+        assert_eq!(std::fs::read_to_string(&log_path).unwrap(), "second line\n");
+        // The rotated file is the one we opened before rotation
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+            assert_eq!(
+                original.metadata().unwrap().ino(),
+                std::fs::metadata(&rotated).unwrap().ino()
+            );
+        }
+        #[cfg(not(unix))]
+        drop(original);
+
+        cleanup_dir(&dir);
+    }
+
+    #[test]
+    fn test_rotating_writer_enforces_total_size() {
+        let dir = unique_test_dir("total_size");
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let log_path = dir.join("test.log");
+        std::fs::write(&log_path, b"").unwrap();
+        let config = FileLogConfig::new(&log_path)
+            .with_rotation_trigger(RotationTrigger::size(20, 10))
+            .with_max_total_size(50);
+        let mut writer = RotatingWriter::from_config(&config).expect("create writer");
+
+        // Each line fills a file, so every write rotates
+        for i in 0..6 {
+            writer
+                .write_all(format!("line {} padding..\n", i).as_bytes())
+                .unwrap();
+        }
+        writer.flush().unwrap();
+
+        // 18 bytes per file: the active file plus the two newest archives fit
+        assert!(log_path.exists());
+        assert!(dir.join("test.log.1").exists());
+        assert!(dir.join("test.log.2").exists());
+        assert!(!dir.join("test.log.3").exists());
+        assert!(!dir.join("test.log.4").exists());
+        assert_eq!(
+            std::fs::read_to_string(dir.join("test.log.2")).unwrap(),
+            "line 3 padding..\n"
+        );
+
+        cleanup_dir(&dir);
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn test_rotating_writer_total_size_deletes_oldest_periods_first() {
+        let dir = unique_test_dir("total_size_periods");
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let log_path = dir.join("test.log");
+        for name in [
+            "test.log.2000-01-01",
+            "test.log.2000-01-01.1",
+            "test.log.2000-01-02.1.gz",
+            "test.log.2000-01-02",
+        ] {
+            std::fs::write(dir.join(name), b"0123456789").unwrap();
+        }
+
+        let config = FileLogConfig::new(&log_path)
+            .with_rotation_trigger(RotationTrigger::both(RotationPeriod::Daily, 1024, 3))
+            .with_max_total_size(25);
+        let _writer = RotatingWriter::from_config(&config).expect("create writer");
+
+        assert!(!dir.join("test.log.2000-01-01").exists());
+        assert!(!dir.join("test.log.2000-01-01.1").exists());
+        assert!(dir.join("test.log.2000-01-02.1.gz").exists());
+        assert!(dir.join("test.log.2000-01-02").exists());
+
+        cleanup_dir(&dir);
+    }
+
+    #[test]
+    fn test_rotating_writer_naming_template_size() {
+        let dir = unique_test_dir("naming_size");
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let log_path = dir.join("test.log");
+        std::fs::write(&log_path, b"").unwrap();
+        let config = FileLogConfig::new(&log_path)
+            .with_rotation_trigger(RotationTrigger::size(20, 2))
+            .with_naming(FileNaming::new("{stem}-{date}.{index}.{ext}").unwrap());
+        let mut writer = RotatingWriter::from_config(&config).expect("create writer");
+
+        for i in 0..4 {
+            writer
+                .write_all(format!("line {} padding..\n", i).as_bytes())
+                .unwrap();
+        }
+        writer.flush().unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(&log_path).unwrap(),
+            "line 3 padding..\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.join("test.1.log")).unwrap(),
+            "line 2 padding..\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.join("test.2.log")).unwrap(),
+            "line 1 padding..\n"
+        );
+        assert!(!dir.join("test.3.log").exists());
+
+        cleanup_dir(&dir);
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn test_rotating_writer_naming_template_time() {
+        let dir = unique_test_dir("naming_time");
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let log_path = dir.join("test.log");
+        for name in [
+            "test-2000-01-01.log",
+            "test-2000-01-01.1.log.gz",
+            "test-2000-01-02.log",
+        ] {
+            std::fs::write(dir.join(name), b"old\n").unwrap();
+        }
+
+        let config = FileLogConfig::new(&log_path)
+            .with_rotation_trigger(RotationTrigger::both(RotationPeriod::Daily, 20, 3))
+            .with_naming(FileNaming::new("{stem}-{date}.{index}.{ext}").unwrap())
+            .with_retention(Retention::periods(1));
+        let mut writer = RotatingWriter::from_config(&config).expect("create writer");
+        writer.write_all(b"line 0 padding..\n").unwrap();
+        writer.write_all(b"line 1 padding..\n").unwrap();
+        writer.flush().unwrap();
+
+        let suffix = RotationPeriod::Daily.get_suffix();
+        assert!(dir.join(format!("test-{}.log", suffix)).exists());
+        assert!(dir.join(format!("test-{}.1.log", suffix)).exists());
+        assert!(!dir.join("test-2000-01-01.log").exists());
+        assert!(!dir.join("test-2000-01-01.1.log.gz").exists());
+        assert!(dir.join("test-2000-01-02.log").exists());
+
+        cleanup_dir(&dir);
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn test_rotating_writer_resumes_time_suffixed_file() {
+        let dir = unique_test_dir("resume_time");
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let log_path = dir.join("test.log");
+        let current = dir.join(format!("test.log.{}", RotationPeriod::Daily.get_suffix()));
+        std::fs::write(&current, b"before restart\n").unwrap();
+
+        let mut writer =
+            RotatingWriter::new(&log_path, RotationTrigger::time(RotationPeriod::Daily))
+                .expect("create writer");
+        writer.write_all(b"after restart\n").unwrap();
+        writer.flush().unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(&current).unwrap(),
+            "before restart\nafter restart\n"
+        );
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+
+        cleanup_dir(&dir);
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn test_rotating_writer_resumes_hybrid_chain() {
+        let dir = unique_test_dir("resume_hybrid");
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let log_path = dir.join("test.log");
+        let current = dir.join(format!("test.log.{}", RotationPeriod::Daily.get_suffix()));
+        let rotated = PathBuf::from(format!("{}.1", current.display()));
+        std::fs::write(&current, b"0123456789\n").unwrap();
+        std::fs::write(&rotated, b"rotated before restart\n").unwrap();
+        // An oversized base file from size-only rotation must not matter
+        std::fs::write(&log_path, vec![b'x'; 100]).unwrap();
+
+        let mut writer = RotatingWriter::new(
+            &log_path,
+            RotationTrigger::both(RotationPeriod::Daily, 32, 3),
+        )
+        .expect("create writer");
+
+        // Fits into the current file: no rotation
+        writer.write_all(b"0123456789\n").unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&rotated).unwrap(),
+            "rotated before restart\n"
+        );
+
+        // Exceeds the size: continues the existing chain
+        writer.write_all(b"0123456789\n").unwrap();
+        let second = PathBuf::from(format!("{}.2", current.display()));
+        assert_eq!(
+            std::fs::read_to_string(&second).unwrap(),
+            "rotated before restart\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(&rotated).unwrap(),
+            "0123456789\n0123456789\n"
+        );
+
+        cleanup_dir(&dir);
+    }
+
+    #[test]
+    fn test_rotating_writer_does_not_rotate_on_restart() {
+        let dir = unique_test_dir("resume_oversized");
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let log_path = dir.join("test.log");
+        std::fs::write(&log_path, vec![b'x'; 100]).unwrap();
+
+        let _writer =
+            RotatingWriter::new(&log_path, RotationTrigger::size(50, 3)).expect("create writer");
+
+        assert_eq!(std::fs::metadata(&log_path).unwrap().len(), 100);
+        assert!(!dir.join("test.log.1").exists());
+
+        cleanup_dir(&dir);
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn test_rotating_writer_rotates_at_end_of_period() {
+        let dir = unique_test_dir("period_end");
+        let log_path = dir.join("test.log");
+        // One second before midnight at the end of January
+        let clock = crate::clock::ManualClock::from_unix_timestamp(1_769_903_999);
+
+        let config = FileLogConfig::new(&log_path).with_rotation_trigger(RotationTrigger::both(
+            RotationPeriod::Monthly,
+            1024,
+            3,
+        ));
+        let mut writer =
+            RotatingWriter::from_config_with_clock(&config, Timezone::Utc, Arc::new(clock.clone()))
+                .expect("create writer");
+        writer.write_all(b"january\n").unwrap();
+        writer.write_all(b"still january\n").unwrap();
+
+        clock.advance(Duration::from_secs(1));
+        writer.write_all(b"february\n").unwrap();
+        writer.flush().unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(dir.join("test.log.2026-01")).unwrap(),
+            "january\nstill january\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.join("test.log.2026-02")).unwrap(),
+            "february\n"
+        );
+
+        cleanup_dir(&dir);
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn test_rotating_writer_follows_dst_change() {
+        let dir = unique_test_dir("dst");
+        let log_path = dir.join("test.log");
+        let at = |secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs);
+        // Central European time, switching to summer time at 2026-03-29T01:00Z
+        let clock = crate::clock::ManualClock::from_unix_timestamp(1_774_699_200);
+        clock.set_local_offset(SystemTime::UNIX_EPOCH, 3600);
+        clock.set_local_offset(at(1_774_746_000), 7200);
+
+        let config = FileLogConfig::new(&log_path)
+            .with_rotation_trigger(RotationTrigger::time(RotationPeriod::Daily));
+        let mut writer = RotatingWriter::from_config_with_clock(
+            &config,
+            Timezone::Local,
+            Arc::new(clock.clone()),
+        )
+        .expect("create writer");
+        writer.write_all(b"saturday\n").unwrap();
+
+        // Midnight at +01:00
+        clock.set(at(1_774_738_800));
+        writer.write_all(b"sunday\n").unwrap();
+
+        // One second before midnight at +02:00, then midnight
+        clock.set(at(1_774_821_599));
+        writer.write_all(b"still sunday\n").unwrap();
+        clock.advance(Duration::from_secs(1));
+        writer.write_all(b"monday\n").unwrap();
+        writer.flush().unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(dir.join("test.log.2026-03-28")).unwrap(),
+            "saturday\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.join("test.log.2026-03-29")).unwrap(),
+            "sunday\nstill sunday\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.join("test.log.2026-03-30")).unwrap(),
+            "monday\n"
+        );
+
+        cleanup_dir(&dir);
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn test_rotating_writer_iso_weeks() {
+        let dir = unique_test_dir("iso_weeks");
+        let log_path = dir.join("test.log");
+        // Wednesday 2025-12-24, in 2025-W52
+        let clock = crate::clock::ManualClock::from_unix_timestamp(1_766_577_600);
+        let week = Duration::from_secs(7 * 24 * 60 * 60);
+
+        let config = FileLogConfig::new(&log_path)
+            .with_rotation_trigger(RotationTrigger::time(RotationPeriod::weekly(
+                crate::Weekday::Monday,
+                crate::WeekSuffix::IsoWeek,
+            )))
+            .with_retention(Retention::periods(1));
+        let mut writer =
+            RotatingWriter::from_config_with_clock(&config, Timezone::Utc, Arc::new(clock.clone()))
+                .expect("create writer");
+        for _ in 0..2 {
+            writer.write_all(b"hello\n").unwrap();
+            clock.advance(week);
+        }
+        writer.write_all(b"hello\n").unwrap();
+
+        // 2026-W02 is active, 2026-W01 is kept and 2025-W52 expired
+        assert!(dir.join("test.log.2026-W02").exists());
+        assert!(dir.join("test.log.2026-W01").exists());
+        assert!(!dir.join("test.log.2025-W52").exists());
+
+        cleanup_dir(&dir);
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn test_rotating_writer_uses_timezone() {
+        let dir = unique_test_dir("timezone");
+        let log_path = dir.join("test.log");
+
+        // Far enough apart that the dates always differ
+        for hours in [14, -12] {
+            let config = FileLogConfig::new(&log_path)
+                .with_rotation_trigger(RotationTrigger::time(RotationPeriod::Daily));
+            let timezone = Timezone::fixed(hours, 0).unwrap();
+            let mut writer =
+                RotatingWriter::from_config_with_timezone(&config, timezone).expect("writer");
+            writer.write_all(b"message\n").unwrap();
+            writer.flush().unwrap();
+
+            let now = time::OffsetDateTime::now_utc().to_offset(timezone.offset());
+            let suffix = RotationPeriod::Daily.suffix_at(now);
+            assert!(dir.join(format!("test.log.{}", suffix)).exists());
+        }
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 2);
+
+        cleanup_dir(&dir);
+    }
+
+    #[cfg(all(unix, feature = "time"))]
+    #[test]
+    fn test_rotating_writer_symlink_to_active_file() {
+        let dir = unique_test_dir("symlink");
+        let log_path = dir.join("test.log");
+
+        let config = FileLogConfig::new(&log_path)
+            .with_rotation_trigger(RotationTrigger::both(RotationPeriod::Daily, 20, 3))
+            .with_symlink("test.log");
+        let mut writer = RotatingWriter::from_config(&config).expect("create writer");
+        writer.write_all(b"first message\n").unwrap();
+        writer.write_all(b"second message\n").unwrap();
+        writer.flush().unwrap();
+
+        let suffix = RotationPeriod::Daily.get_suffix();
+        let target = std::fs::read_link(&log_path).expect("symlink");
+        assert_eq!(target, PathBuf::from(format!("test.log.{}", suffix)));
+        assert_eq!(
+            std::fs::read_to_string(&log_path).unwrap(),
+            "second message\n"
+        );
+        // Only the active file, its rotated sibling and the link
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 3);
+
+        cleanup_dir(&dir);
+    }
+
+    #[cfg(all(unix, feature = "time"))]
+    #[test]
+    fn test_rotating_writer_symlink_keeps_regular_file() {
+        let dir = unique_test_dir("symlink_regular");
+        std::fs::create_dir_all(&dir).unwrap();
+        let log_path = dir.join("test.log");
+        std::fs::write(&log_path, b"from an earlier setup\n").unwrap();
+
+        let config = FileLogConfig::new(&log_path)
+            .with_rotation_trigger(RotationTrigger::time(RotationPeriod::Daily))
+            .with_symlink("test.log");
+        let mut writer = RotatingWriter::from_config(&config).expect("create writer");
+        writer.write_all(b"message\n").unwrap();
+
+        assert!(!log_path.is_symlink());
+        assert_eq!(
+            std::fs::read_to_string(&log_path).unwrap(),
+            "from an earlier setup\n"
+        );
+
+        cleanup_dir(&dir);
+    }
+
+    #[test]
+    fn test_rotating_writer_rotation_hooks() {
+        let dir = unique_test_dir("hooks");
+        let log_path = dir.join("test.log");
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&events);
+        let mut writer = RotatingWriter::new(&log_path, RotationTrigger::size(50, 3))
+            .expect("create writer")
+            .on_rotate(move |event| recorded.lock().unwrap().push(event.clone()));
+
+        for i in 0..3 {
+            writer
+                .write_all(format!("line {} - some padding text here\n", i).as_bytes())
+                .unwrap();
+        }
+        // Dropping the writer waits for pending hooks
+        drop(writer);
+
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 2);
+        for event in events.iter() {
+            assert_eq!(event.closed, dir.join("test.log.1"));
+            assert_eq!(event.opened, log_path);
+            assert_eq!(event.reason, RotationReason::Size);
+            assert_eq!(event.bytes, 32);
+        }
+
+        cleanup_dir(&dir);
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn test_rotating_writer_rotation_hooks_after_compression() {
+        let dir = unique_test_dir("hooks_compress");
+        let log_path = dir.join("test.log");
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&events);
+        let config = FileLogConfig::new(&log_path)
+            .with_rotation_trigger(RotationTrigger::size(50, 3))
+            .with_compression(Compression::Gzip);
+        let mut writer = RotatingWriter::from_config(&config)
+            .expect("create writer")
+            .on_rotate(move |event| {
+                // The compressed file is in place when the hook runs
+                assert!(event.closed.exists());
+                recorded.lock().unwrap().push(event.clone());
+            });
+
+        writer
+            .write_all(b"line 0 - some padding text here\n")
+            .unwrap();
+        writer
+            .write_all(b"line 1 - some padding text here\n")
+            .unwrap();
+        drop(writer);
+
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].closed, dir.join("test.log.1.gz"));
+        assert_eq!(events[0].bytes, 32);
+
+        cleanup_dir(&dir);
+    }
+
+    #[test]
+    fn test_rotating_writer_reopens_moved_file() {
+        let dir = unique_test_dir("reopen_moved");
+        let log_path = dir.join("test.log");
+        let moved_path = dir.join("test.log.moved");
+
+        let config = FileLogConfig::new(&log_path).with_reopen_check(Duration::ZERO);
+        let mut writer = RotatingWriter::from_config(&config).expect("create writer");
+        writer.write_all(b"before\n").unwrap();
+
+        // Like logrotate: move the file away, then remove it entirely
+        std::fs::rename(&log_path, &moved_path).unwrap();
+        writer.write_all(b"after move\n").unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        writer.write_all(b"after delete\n").unwrap();
+        writer.flush().unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(&log_path).unwrap(),
+            "after delete\n"
+        );
+
+        cleanup_dir(&dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_rotating_writer_write_failure_retry() {
+        let dir = unique_test_dir("write_failure");
+        std::fs::create_dir_all(&dir).unwrap();
+        let log_path = dir.join("test.log");
+
+        let config =
+            FileLogConfig::new(&log_path).with_write_failure(crate::WriteFailurePolicy::Retry);
+        let mut writer = RotatingWriter::from_config(&config).expect("create writer");
+        writer.write_all(b"before\n").unwrap();
+
+        // Swap the file for a read-only handle so that writes fail
+        let read_only = BufWriter::with_capacity(0, File::open(&log_path).unwrap());
+        let writable = std::mem::replace(
+            &mut writer.inner.state.lock().unwrap().as_mut().unwrap().file,
+            read_only,
+        );
+        drop(writable);
+
+        // Failed and backed-off records are dropped, not returned as errors
+        writer.write_all(b"lost\n").unwrap();
+        writer.write_all(b"lost while backing off\n").unwrap();
+        assert_eq!(writer.dropped_records(), 2);
+
+        // The retry reopens the file by name
+        std::thread::sleep(Duration::from_millis(150));
+        writer.write_all(b"after\n").unwrap();
+        writer.flush().unwrap();
+        assert_eq!(writer.dropped_records(), 2);
+        assert_eq!(
+            std::fs::read_to_string(&log_path).unwrap(),
+            "before\nafter\n"
+        );
+
+        cleanup_dir(&dir);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_rotating_writer_reopen_handles_unwritten_records() {
+        let dir = unique_test_dir("reopen_unwritten");
+        std::fs::create_dir_all(&dir).unwrap();
+        let log_path = dir.join("test.log");
+        // Writes to /dev/full fail with "no space left on device"
+        std::os::unix::fs::symlink("/dev/full", &log_path).unwrap();
+
+        let config = FileLogConfig::new(&log_path).with_durability(crate::Durability::None);
+        let mut writer = RotatingWriter::from_config(&config).expect("create writer");
+        writer.write_all(b"buffered 0\n").unwrap();
+        writer.write_all(b"buffered 1\n").unwrap();
+        assert_eq!(writer.dropped_records(), 0);
+
+        // The buffered records can't be written out when the file is closed
+        writer.reopen().unwrap();
+        assert_eq!(writer.dropped_records(), 2);
+
+        cleanup_dir(&dir);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_rotating_writer_flush_handles_unwritten_records() {
+        let dir = unique_test_dir("flush_unwritten");
+        std::fs::create_dir_all(&dir).unwrap();
+        let log_path = dir.join("test.log");
+        // Writes to /dev/full fail with "no space left on device"
+        std::os::unix::fs::symlink("/dev/full", &log_path).unwrap();
+
+        let config = FileLogConfig::new(&log_path).with_durability(crate::Durability::Flush);
+        let mut writer = RotatingWriter::from_config(&config).expect("create writer");
+        writer.write_all(b"buffered 0\n").unwrap();
+        writer.write_all(b"buffered 1\n").unwrap();
+        assert_eq!(writer.dropped_records(), 0);
+
+        // Reported like a failed write, not handed back to the caller
+        writer.flush().unwrap();
+        assert_eq!(writer.dropped_records(), 2);
+        writer.flush().unwrap();
+        assert_eq!(writer.dropped_records(), 2);
+
+        cleanup_dir(&dir);
+    }
+
+    #[test]
+    fn test_rotating_writer_buffered_durability() {
+        let dir = unique_test_dir("durability");
+        let log_path = dir.join("test.log");
+
+        let config = FileLogConfig::new(&log_path)
+            .with_rotation_trigger(RotationTrigger::size(50, 3))
+            .with_durability(crate::Durability::None);
+        let mut writer = RotatingWriter::from_config(&config).expect("create writer");
+
+        // Without durability, flushing leaves records in the buffer
+        writer
+            .write_all(b"line 0 - some padding text here\n")
+            .unwrap();
+        writer.flush().unwrap();
+        assert_eq!(std::fs::read_to_string(&log_path).unwrap(), "");
+
+        // Rotation writes out the buffer first, so sizes stay exact
+        writer
+            .write_all(b"line 1 - some padding text here\n")
+            .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(dir.join("test.log.1")).unwrap(),
+            "line 0 - some padding text here\n"
+        );
+        drop(writer);
+        assert_eq!(
+            std::fs::read_to_string(&log_path).unwrap(),
+            "line 1 - some padding text here\n"
+        );
+
+        let config = FileLogConfig::new(&log_path).with_durability(crate::Durability::FsyncOnFlush);
+        let mut writer = RotatingWriter::from_config(&config).expect("create writer");
+        writer.write_all(b"synced\n").unwrap();
+        writer.flush().unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&log_path).unwrap(),
+            "line 1 - some padding text here\nsynced\n"
+        );
+
+        cleanup_dir(&dir);
+    }
+
+    #[test]
+    fn test_rotating_writer_multi_process() {
+        for strategy in [RotationStrategy::CopyTruncate, RotationStrategy::Rename] {
+            let dir = unique_test_dir("multi_process");
+            let log_path = dir.join("test.log");
+
+            // Writers opened separately hold separate locks, like processes
+            let config = FileLogConfig::new(&log_path)
+                .with_rotation_trigger(RotationTrigger::size(100, 100))
+                .with_strategy(strategy)
+                .with_multi_process(true);
+            let workers: Vec<_> = (0..2)
+                .map(|worker| {
+                    let mut writer = RotatingWriter::from_config(&config).expect("create writer");
+                    std::thread::spawn(move || {
+                        for i in 0..50 {
+                            writer
+                                .write_all(format!("worker {} line {:02}\n", worker, i).as_bytes())
+                                .unwrap();
+                        }
+                    })
+                })
+                .collect();
+            for worker in workers {
+                worker.join().unwrap();
+            }
+
+            let mut lines = Vec::new();
+            for entry in std::fs::read_dir(&dir).unwrap() {
+                let path = entry.unwrap().path();
+                if path.file_name().unwrap().to_str().unwrap().starts_with('.') {
+                    continue;
+                }
+                let content = std::fs::read_to_string(&path).unwrap();
+                assert!(
+                    content.len() <= 100,
+                    "{:?} has {} bytes",
+                    path,
+                    content.len()
+                );
+                lines.extend(content.lines().map(str::to_string));
+            }
+
+            // Every record survived exactly once
+            lines.sort();
+            let mut expected: Vec<_> = (0..2)
+                .flat_map(|worker| (0..50).map(move |i| format!("worker {} line {:02}", worker, i)))
+                .collect();
+            expected.sort();
+            assert_eq!(lines, expected, "{:?}", strategy);
+
+            cleanup_dir(&dir);
+        }
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn test_rotating_writer_multi_process_compression() {
+        use std::io::Read;
+
+        let dir = unique_test_dir("multi_process_compress");
         std::fs::create_dir_all(&dir).unwrap();
+        let log_path = dir.join("test.log");
+        let old: String = (0..200_000)
+            .map(|i| format!("old line {:06}\n", i))
+            .collect();
+        std::fs::write(&log_path, &old).unwrap();
+
+        // Writers opened separately hold separate locks, like processes
+        let config = FileLogConfig::new(&log_path)
+            .with_rotation_trigger(RotationTrigger::size(1024, 10))
+            .with_compression(Compression::Gzip)
+            .with_multi_process(true);
+        let mut first = RotatingWriter::from_config(&config).expect("create writer");
+        let mut second = RotatingWriter::from_config(&config).expect("create writer");
+
+        // The first writer archives the large file, and the second one shifts
+        // it down the chain while it's still being compressed
+        first.write_all(b"first\n").unwrap();
+        for i in 0..50 {
+            second
+                .write_all(format!("second line {:02} - some padding\n", i).as_bytes())
+                .unwrap();
+        }
+        drop(first);
+        drop(second);
+
+        let mut lines = Vec::new();
+        for entry in std::fs::read_dir(&dir).unwrap() {
+            let path = entry.unwrap().path();
+            let name = path.file_name().unwrap().to_str().unwrap();
+            assert!(!name.ends_with(".tmp"), "{} left behind", name);
+            if name.starts_with('.') {
+                continue;
+            }
+            let mut content = String::new();
+            if name.ends_with(".gz") {
+                flate2::read::GzDecoder::new(File::open(&path).unwrap())
+                    .read_to_string(&mut content)
+                    .unwrap();
+            } else {
+                content = std::fs::read_to_string(&path).unwrap();
+            }
+            lines.extend(content.lines().map(str::to_string));
+        }
+
+        // Every record survived exactly once
+        lines.sort();
+        let mut expected: Vec<_> = old.lines().map(str::to_string).collect();
+        expected.push("first".to_string());
+        expected.extend((0..50).map(|i| format!("second line {:02} - some padding", i)));
+        expected.sort();
+        assert_eq!(lines.len(), expected.len());
+        assert!(lines == expected);
 
+        cleanup_dir(&dir);
+    }
+
+    #[test]
+    fn test_rotating_writer_rotates_between_records() {
+        let dir = unique_test_dir("record_boundaries");
         let log_path = dir.join("test.log");
-        // Very small max_size to trigger rotation quickly
+
         let mut writer =
             RotatingWriter::new(&log_path, RotationTrigger::size(50, 3)).expect("create writer");
+        writer
+            .write_all(b"first record with some padding\n")
+            .unwrap();
+        // A record arriving in pieces stays in one file
+        writer.write_all(b"second record").unwrap();
+        writer.write_all(b" continued\n").unwrap();
+        writer.write_all(b"third record\n").unwrap();
+        drop(writer);
+
+        assert_eq!(
+            std::fs::read_to_string(dir.join("test.log.1")).unwrap(),
+            "first record with some padding\nsecond record continued\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(&log_path).unwrap(),
+            "third record\n"
+        );
 
-        // Write enough to trigger rotation
-        for i in 0..5 {
-            writer
-                .write_all(format!("line {} - some padding text here\n", i).as_bytes())
-                .unwrap();
+        cleanup_dir(&dir);
+    }
+
+    #[test]
+    fn test_rotating_writer_oversized_record_own_file() {
+        let dir = unique_test_dir("oversized_own_file");
+        let log_path = dir.join("test.log");
+
+        let mut writer =
+            RotatingWriter::new(&log_path, RotationTrigger::size(20, 5)).expect("create writer");
+        let oversized = format!("{}\n", "x".repeat(59));
+        writer.write_all(b"small\n").unwrap();
+        writer.write_all(oversized.as_bytes()).unwrap();
+        writer.write_all(b"after\n").unwrap();
+        drop(writer);
+
+        // The oversized record rotated once before and once after itself
+        assert_eq!(
+            std::fs::read_to_string(dir.join("test.log.2")).unwrap(),
+            "small\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.join("test.log.1")).unwrap(),
+            oversized
+        );
+        assert_eq!(std::fs::read_to_string(&log_path).unwrap(), "after\n");
+        assert!(!dir.join("test.log.3").exists());
+
+        cleanup_dir(&dir);
+    }
+
+    #[test]
+    fn test_rotating_writer_oversized_record_truncate() {
+        let dir = unique_test_dir("oversized_truncate");
+        let log_path = dir.join("test.log");
+
+        let config = FileLogConfig::new(&log_path)
+            .with_rotation_trigger(RotationTrigger::size(20, 5))
+            .with_oversized_records(OversizedRecord::Truncate);
+        let mut writer = RotatingWriter::from_config(&config).expect("create writer");
+        writer.write_all(b"0123456789abcdefghijklmn\n").unwrap();
+        // Records arriving in pieces are cut as well
+        writer.write_all(b"0123456").unwrap();
+        writer.write_all(b"789abcdefghijklmn").unwrap();
+        writer.write_all(b"opq\n").unwrap();
+        writer.write_all(b"ok\n").unwrap();
+        drop(writer);
+
+        for archive in ["test.log.2", "test.log.1"] {
+            assert_eq!(
+                std::fs::read_to_string(dir.join(archive)).unwrap(),
+                "01234567[truncated]\n"
+            );
         }
-        writer.flush().unwrap();
+        assert_eq!(std::fs::read_to_string(&log_path).unwrap(), "ok\n");
 
-        // Check that rotated files exist
-        assert!(log_path.exists(), "base log file should exist");
-        let rotated_1 = dir.join("test.log.1");
-        assert!(rotated_1.exists(), "test.log.1 should exist");
+        cleanup_dir(&dir);
+    }
+
+    #[test]
+    fn test_rotating_writer_records_without_newlines() {
+        let dir = unique_test_dir("no_newlines");
+        let log_path = dir.join("test.log");
+
+        // Output that never ends a record still rotates, and nothing is lost
+        let config =
+            FileLogConfig::new(&log_path).with_rotation_trigger(RotationTrigger::size(50, 20));
+        let mut writer = RotatingWriter::from_config(&config).expect("create writer");
+        for chunk in 0..10u8 {
+            writer.write_all(&[b'a' + chunk; 100]).unwrap();
+        }
+        drop(writer);
+
+        let mut written = Vec::new();
+        for index in (1..10).rev() {
+            written.extend(std::fs::read(dir.join(format!("test.log.{}", index))).unwrap());
+        }
+        written.extend(std::fs::read(&log_path).unwrap());
+        let expected: Vec<u8> = (0..10u8).flat_map(|chunk| [b'a' + chunk; 100]).collect();
+        assert_eq!(written, expected);
+        cleanup_dir(&dir);
+
+        // Cut records end once they reach twice the limit, and what follows is kept
+        let config = config.with_oversized_records(OversizedRecord::Truncate);
+        let mut writer = RotatingWriter::from_config(&config).expect("create writer");
+        for chunk in 0..10u8 {
+            writer.write_all(&[b'a' + chunk; 100]).unwrap();
+        }
+        drop(writer);
+
+        assert_eq!(
+            std::fs::read_to_string(dir.join("test.log.9")).unwrap(),
+            format!("{}[truncated]\n", "a".repeat(38))
+        );
+        assert_eq!(
+            std::fs::read_to_string(&log_path).unwrap(),
+            format!("{}[truncated]\n", "j".repeat(38))
+        );
 
         cleanup_dir(&dir);
     }
 
     #[cfg(feature = "time")]
     #[test]
-    fn test_rotating_writer_time_suffix() {
-        let dir = unique_test_dir("time");
-        std::fs::create_dir_all(&dir).unwrap();
-
+    fn test_rotating_writer_unterminated_record_at_end_of_period() {
+        let dir = unique_test_dir("no_newline_period_end");
         let log_path = dir.join("test.log");
-        let mut writer = RotatingWriter::new(
-            &log_path,
-            RotationTrigger::Time {
-                period: RotationPeriod::Daily,
-            },
-        )
-        .expect("create writer");
+        // One second before midnight at the end of January
+        let clock = crate::clock::ManualClock::from_unix_timestamp(1_769_903_999);
 
-        writer.write_all(b"hello\n").unwrap();
+        let config = FileLogConfig::new(&log_path)
+            .with_rotation_trigger(RotationTrigger::time(RotationPeriod::Daily));
+        let mut writer =
+            RotatingWriter::from_config_with_clock(&config, Timezone::Utc, Arc::new(clock.clone()))
+                .expect("create writer");
+        writer.write_all(b"january").unwrap();
+
+        clock.advance(Duration::from_secs(1));
+        writer.write_all(b" and february").unwrap();
         writer.flush().unwrap();
 
-        // Find the time-suffixed file
-        let entries: Vec<_> = std::fs::read_dir(&dir)
-            .unwrap()
-            .filter_map(|e| e.ok())
-            .collect();
+        assert_eq!(
+            std::fs::read_to_string(dir.join("test.log.2026-01-31")).unwrap(),
+            "january"
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.join("test.log.2026-02-01")).unwrap(),
+            " and february"
+        );
 
-        assert!(!entries.is_empty(), "should have created a log file");
+        cleanup_dir(&dir);
+    }
 
-        // Check that filename has date suffix
-        let first = &entries[0];
-        let name = first.file_name().to_string_lossy().to_string();
-        assert!(
-            name.starts_with("test.log."),
-            "filename should have date suffix"
+    #[test]
+    fn test_rotating_writer_rotate_on_start() {
+        let dir = unique_test_dir("rotate_on_start");
+        let log_path = dir.join("test.log");
+
+        let config = FileLogConfig::new(&log_path)
+            .with_rotation_trigger(RotationTrigger::size(1024, 3))
+            .with_rotate_on_start(true);
+        for run in 0..2 {
+            let mut writer = RotatingWriter::from_config(&config).expect("create writer");
+            writer
+                .write_all(format!("run {}\n", run).as_bytes())
+                .unwrap();
+        }
+        // An empty file isn't rotated
+        drop(RotatingWriter::from_config(&config).expect("create writer"));
+        drop(RotatingWriter::from_config(&config).expect("create writer"));
+
+        assert_eq!(
+            std::fs::read_to_string(dir.join("test.log.2")).unwrap(),
+            "run 0\n"
         );
-        assert!(name.contains('-'), "filename should contain date separator");
+        assert_eq!(
+            std::fs::read_to_string(dir.join("test.log.1")).unwrap(),
+            "run 1\n"
+        );
+        assert_eq!(std::fs::read_to_string(&log_path).unwrap(), "");
+        assert!(!dir.join("test.log.3").exists());
 
         cleanup_dir(&dir);
     }
 
     #[test]
-    fn test_rotating_writer_reuse_existing_file() {
-        let dir = unique_test_dir("reuse");
-        std::fs::create_dir_all(&dir).unwrap();
+    fn test_rotating_writer_rotate_now() {
+        let dir = unique_test_dir("rotate_now");
+        let log_path = dir.join("test.log");
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&events);
+        let mut writer = RotatingWriter::new(&log_path, RotationTrigger::Never)
+            .expect("create writer")
+            .on_rotate(move |event| recorded.lock().unwrap().push(event.clone()));
+        writer.write_all(b"before\n").unwrap();
+        writer.rotate_now().unwrap();
+        writer.write_all(b"after\n").unwrap();
+        drop(writer);
+
+        assert_eq!(
+            std::fs::read_to_string(dir.join("test.log.1")).unwrap(),
+            "before\n"
+        );
+        assert_eq!(std::fs::read_to_string(&log_path).unwrap(), "after\n");
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].reason, RotationReason::Manual);
+        assert_eq!(events[0].bytes, 7);
+
+        cleanup_dir(&dir);
+    }
 
+    #[cfg(feature = "time")]
+    #[test]
+    fn test_rotating_writer_rotate_now_with_time_trigger() {
+        let dir = unique_test_dir("rotate_now_time");
         let log_path = dir.join("test.log");
+        let clock = crate::clock::ManualClock::from_unix_timestamp(1_769_860_800);
 
-        // Create a file with some content that's under the size limit
-        {
-            let mut file = std::fs::File::create(&log_path).unwrap();
-            file.write_all(b"existing content\n").unwrap();
+        let config = FileLogConfig::new(&log_path)
+            .with_rotation_trigger(RotationTrigger::time(RotationPeriod::Daily));
+        let mut writer =
+            RotatingWriter::from_config_with_clock(&config, Timezone::Utc, Arc::new(clock.clone()))
+                .expect("create writer");
+        // Without a file limit, no manually rotated file is deleted
+        for i in 0..7 {
+            writer
+                .write_all(format!("record {}\n", i).as_bytes())
+                .unwrap();
+            writer.rotate_now().unwrap();
+        }
+        drop(writer);
+
+        for i in 0..7 {
+            assert_eq!(
+                std::fs::read_to_string(dir.join(format!("test.log.2026-01-31.{}", 7 - i)))
+                    .unwrap(),
+                format!("record {}\n", i)
+            );
         }
 
-        // Create writer with size limit larger than existing content
+        cleanup_dir(&dir);
+    }
+
+    #[test]
+    fn test_rotating_writer_lines_trigger() {
+        let dir = unique_test_dir("lines_trigger");
+        let log_path = dir.join("test.log");
+
         let mut writer =
-            RotatingWriter::new(&log_path, RotationTrigger::size(100, 5)).expect("create writer");
+            RotatingWriter::new(&log_path, RotationTrigger::lines(3, 5)).expect("create writer");
+        for i in 0..4 {
+            writer
+                .write_all(format!("line {}\n", i).as_bytes())
+                .unwrap();
+        }
+        drop(writer);
 
-        // Write additional content
-        writer.write_all(b"new content\n").unwrap();
-        writer.flush().unwrap();
+        // Resuming counts the records already in the file
+        let mut writer =
+            RotatingWriter::new(&log_path, RotationTrigger::lines(3, 5)).expect("create writer");
+        for i in 4..7 {
+            writer
+                .write_all(format!("line {}\n", i).as_bytes())
+                .unwrap();
+        }
+        drop(writer);
 
-        // Check that the file still contains both old and new content
-        let content = std::fs::read_to_string(&log_path).unwrap();
-        assert!(content.contains("existing content"));
-        assert!(content.contains("new content"));
+        assert_eq!(
+            std::fs::read_to_string(dir.join("test.log.2")).unwrap(),
+            "line 0\nline 1\nline 2\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.join("test.log.1")).unwrap(),
+            "line 3\nline 4\nline 5\n"
+        );
+        assert_eq!(std::fs::read_to_string(&log_path).unwrap(), "line 6\n");
 
-        // Check that no rotated files were created
-        let rotated_1 = dir.join("test.log.1");
-        assert!(!rotated_1.exists(), "Should not have created rotated file");
+        cleanup_dir(&dir);
+    }
+
+    #[test]
+    fn test_rotating_writer_header() {
+        let dir = unique_test_dir("header");
+        let log_path = dir.join("test.log");
+
+        let config = FileLogConfig::new(&log_path)
+            .with_rotation_trigger(RotationTrigger::size(70, 3))
+            .with_header(crate::FileHeader::text("# previous: {previous}"));
+        let mut writer = RotatingWriter::from_config(&config).expect("create writer");
+        // The header counts toward the size, so each record gets its own file
+        writer
+            .write_all(b"line 0 - some padding text here\n")
+            .unwrap();
+        writer
+            .write_all(b"line 1 - some padding text here\n")
+            .unwrap();
+        drop(writer);
+
+        // A resumed file keeps its header
+        drop(RotatingWriter::from_config(&config).expect("create writer"));
+
+        assert_eq!(
+            std::fs::read_to_string(dir.join("test.log.1")).unwrap(),
+            "# previous: \nline 0 - some padding text here\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(&log_path).unwrap(),
+            format!(
+                "# previous: {}\nline 1 - some padding text here\n",
+                dir.join("test.log.1").display()
+            )
+        );
 
         cleanup_dir(&dir);
     }
 
-    #[cfg(feature = "time")]
     #[test]
-    fn test_rotating_writer_hybrid() {
-        let dir = unique_test_dir("hybrid");
+    fn test_rotating_writer_header_with_lines_trigger() {
+        let dir = unique_test_dir("header_lines");
+        let log_path = dir.join("test.log");
+
+        let config = FileLogConfig::new(&log_path)
+            .with_rotation_trigger(RotationTrigger::lines(3, 5))
+            .with_header(crate::FileHeader::text("# pid {pid}"));
+        let header = format!("# pid {}\n", std::process::id());
+        let mut writer = RotatingWriter::from_config(&config).expect("create writer");
+        for i in 0..4 {
+            writer
+                .write_all(format!("line {}\n", i).as_bytes())
+                .unwrap();
+        }
+        drop(writer);
+
+        // Resuming counts the records after the header
+        let mut writer = RotatingWriter::from_config(&config).expect("create writer");
+        for i in 4..6 {
+            writer
+                .write_all(format!("line {}\n", i).as_bytes())
+                .unwrap();
+        }
+        drop(writer);
+
+        assert_eq!(
+            std::fs::read_to_string(dir.join("test.log.1")).unwrap(),
+            format!("{}line 0\nline 1\nline 2\n", header)
+        );
+        assert_eq!(
+            std::fs::read_to_string(&log_path).unwrap(),
+            format!("{}line 3\nline 4\nline 5\n", header)
+        );
+
+        // A resumed file holding just the header has no records to rotate
+        let header_only = dir.join("header_only.log");
+        let config = FileLogConfig::new(&header_only)
+            .with_rotation_trigger(RotationTrigger::lines(3, 5))
+            .with_header(crate::FileHeader::text("# pid {pid}"));
+        drop(RotatingWriter::from_config(&config).expect("create writer"));
+        let writer =
+            RotatingWriter::from_config(&config.with_rotate_on_start(true)).expect("create writer");
+        writer.rotate_now().unwrap();
+        assert!(!dir.join("header_only.log.1").exists());
+        assert_eq!(std::fs::read_to_string(&header_only).unwrap(), header);
+
+        cleanup_dir(&dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_rotating_writer_permissions() {
+        use std::os::unix::fs::{MetadataExt, PermissionsExt};
+
+        let dir = unique_test_dir("permissions");
+        let log_dir = dir.join("nested");
+        let log_path = log_dir.join("test.log");
         std::fs::create_dir_all(&dir).unwrap();
+        let uid = std::fs::metadata(&dir).unwrap().uid();
+
+        let config = FileLogConfig::new(&log_path)
+            .with_rotation_trigger(RotationTrigger::size(50, 3))
+            .with_file_mode(0o600)
+            .with_dir_mode(0o700)
+            .with_owner(Some(uid), None);
+        let mut writer = RotatingWriter::from_config(&config).expect("create writer");
+        writer
+            .write_all(b"line 0 - some padding text here\n")
+            .unwrap();
+        writer
+            .write_all(b"line 1 - some padding text here\n")
+            .unwrap();
+        drop(writer);
+
+        let mode = |path: &Path| std::fs::metadata(path).unwrap().permissions().mode() & 0o7777;
+        assert_eq!(mode(&log_dir), 0o700);
+        assert_eq!(mode(&log_path), 0o600);
+        assert_eq!(mode(&log_dir.join("test.log.1")), 0o600);
+        assert_eq!(std::fs::metadata(&log_path).unwrap().uid(), uid);
+
+        // An existing file is brought in line as well
+        std::fs::set_permissions(&log_path, std::fs::Permissions::from_mode(0o644)).unwrap();
+        drop(RotatingWriter::from_config(&config).expect("create writer"));
+        assert_eq!(mode(&log_path), 0o600);
 
-        let log_path = dir.join("test.log");
-        let mut writer = RotatingWriter::new(
-            &log_path,
-            RotationTrigger::both(RotationPeriod::Daily, 50, 3),
-        )
-        .expect("create writer");
+        cleanup_dir(&dir);
+    }
 
-        // Write enough to trigger size rotation
-        for i in 0..5 {
+    #[test]
+    fn test_rotating_writer_archive_dir() {
+        let dir = unique_test_dir("archive_dir");
+        let log_path = dir.join("live").join("test.log");
+        let archive_dir = dir.join("archive");
+
+        let config = FileLogConfig::new(&log_path)
+            .with_rotation_trigger(RotationTrigger::size(50, 2))
+            .with_strategy(RotationStrategy::Rename)
+            .with_archive_dir("../archive");
+        let mut writer = RotatingWriter::from_config(&config).expect("create writer");
+        for i in 0..4 {
             writer
-                .write_all(format!("line {} padding\n", i).as_bytes())
+                .write_all(format!("line {} - some padding text here\n", i).as_bytes())
                 .unwrap();
         }
-        writer.flush().unwrap();
+        drop(writer);
 
-        // Should have created files
-        let entries: Vec<_> = std::fs::read_dir(&dir)
+        // Only the active file stays in the live directory
+        let live: Vec<_> = std::fs::read_dir(dir.join("live"))
             .unwrap()
-            .filter_map(|e| e.ok())
+            .map(|e| e.unwrap().file_name())
             .collect();
+        assert_eq!(live, ["test.log"]);
+        assert_eq!(
+            std::fs::read_to_string(archive_dir.join("test.log.1")).unwrap(),
+            "line 2 - some padding text here\n"
+        );
+        assert!(archive_dir.join("test.log.2").exists());
+        assert!(!archive_dir.join("test.log.3").exists());
 
-        assert!(!entries.is_empty(), "should have created log files");
+        cleanup_dir(&dir);
+    }
 
-        // Find the active time-suffixed file (e.g., test.log.2026-01-15)
-        // Choose the suffixed name whose last segment contains a '-' (date),
-        // which distinguishes it from rotated numeric suffixes like `.1`.
-        let active = entries
-            .iter()
-            .map(|e| e.file_name().to_string_lossy().to_string())
-            .find(|name| {
-                name.starts_with("test.log.")
-                    && name
-                        .rsplit('.')
-                        .next()
-                        .map(|s| s.contains('-'))
-                        .unwrap_or(false)
-            })
-            .expect("active time-suffixed file should exist");
+    #[cfg(feature = "time")]
+    #[test]
+    fn test_rotating_writer_archive_dir_moves_leftovers() {
+        let dir = unique_test_dir("archive_dir_leftovers");
+        std::fs::create_dir_all(&dir).unwrap();
+        let log_path = dir.join("test.log");
+        let archive_dir = dir.join("archive");
+
+        for name in [
+            "test.log.2000-01-01",
+            "test.log.2000-01-02",
+            "test.log.2000-01-02.1",
+        ] {
+            std::fs::write(dir.join(name), b"old\n").unwrap();
+        }
+        std::fs::write(dir.join("test.log.unrelated"), b"keep\n").unwrap();
 
-        let rotated_name = format!("{}.1", active);
-        let rotated_path = dir.join(&rotated_name);
-        assert!(
-            rotated_path.exists(),
-            "rotated file {} should exist",
-            rotated_name
+        let config = FileLogConfig::new(&log_path)
+            .with_rotation_trigger(RotationTrigger::both(RotationPeriod::Daily, 1024, 3))
+            .with_retention(Retention::periods(1))
+            .with_archive_dir("archive");
+        let mut writer = RotatingWriter::from_config(&config).expect("create writer");
+        writer.write_all(b"hello\n").unwrap();
+
+        // Moved out of the live directory, then pruned in the archive directory
+        assert!(!dir.join("test.log.2000-01-02").exists());
+        assert!(!archive_dir.join("test.log.2000-01-01").exists());
+        assert!(archive_dir.join("test.log.2000-01-02").exists());
+        assert!(archive_dir.join("test.log.2000-01-02.1").exists());
+        assert!(dir.join("test.log.unrelated").exists());
+
+        cleanup_dir(&dir);
+    }
+
+    #[test]
+    fn test_rotating_writer_explicit_reopen() {
+        let dir = unique_test_dir("reopen_explicit");
+        let log_path = dir.join("test.log");
+        let moved_path = dir.join("test.log.moved");
+
+        let mut writer =
+            RotatingWriter::new(&log_path, RotationTrigger::Never).expect("create writer");
+        let handle = writer.clone();
+        writer.write_all(b"before\n").unwrap();
+
+        std::fs::rename(&log_path, &moved_path).unwrap();
+        // Within the check interval, writes still go to the moved file
+        writer.write_all(b"still old\n").unwrap();
+        handle.reopen().unwrap();
+        writer.write_all(b"after\n").unwrap();
+        writer.flush().unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(&moved_path).unwrap(),
+            "before\nstill old\n"
         );
+        assert_eq!(std::fs::read_to_string(&log_path).unwrap(), "after\n");
 
         cleanup_dir(&dir);
     }
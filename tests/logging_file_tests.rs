@@ -9,18 +9,10 @@ fn test_file_logging_disables_ansi_text() {
     let tmp = tempfile::NamedTempFile::new().expect("temp file");
     let path = tmp.path().to_str().unwrap().to_string();
 
-    let cfg = LogConfig {
-        level: "info".to_string(),
-        console: true,
-        format: "text".to_string(),
-        file: Some(lazylog::FileLogConfig {
-            path: path.clone().into(),
-            rotation: lazylog::RotationTrigger::Never,
-        }),
-        target: false,
-        thread_ids: false,
-        thread_names: false,
-    };
+    let cfg = LogConfig::new()
+        .with_console(true)
+        .with_format("text".to_string())
+        .with_file(lazylog::FileLogConfig::new(path.clone()));
 
     let filter = tracing_subscriber::EnvFilter::try_new(cfg.level.clone()).unwrap();
     let file = std::fs::OpenOptions::new()
@@ -63,18 +55,10 @@ fn test_file_logging_disables_ansi_json() {
     let tmp = tempfile::NamedTempFile::new().expect("temp file");
     let path = tmp.path().to_str().unwrap().to_string();
 
-    let cfg = LogConfig {
-        level: "info".to_string(),
-        console: true,
-        format: "json".to_string(),
-        file: Some(lazylog::FileLogConfig {
-            path: path.clone().into(),
-            rotation: lazylog::RotationTrigger::Never,
-        }),
-        target: false,
-        thread_ids: false,
-        thread_names: false,
-    };
+    let cfg = LogConfig::new()
+        .with_console(true)
+        .with_format("json".to_string())
+        .with_file(lazylog::FileLogConfig::new(path.clone()));
 
     let filter = tracing_subscriber::EnvFilter::try_new(cfg.level.clone()).unwrap();
 
@@ -118,20 +102,16 @@ fn test_rolling_daily_creates_file() {
     let dir = tempfile::tempdir().expect("tempdir");
     let dir_str = dir.path().to_str().unwrap().to_string();
 
-    let cfg = LogConfig {
-        level: "info".to_string(),
-        console: true,
-        format: "text".to_string(),
-        file: Some(lazylog::FileLogConfig {
-            path: dir.path().join("app.log"),
-            rotation: lazylog::RotationTrigger::Time {
-                period: lazylog::RotationPeriod::Daily,
-            },
-        }),
-        target: false,
-        thread_ids: false,
-        thread_names: false,
-    };
+    let cfg = LogConfig::new()
+        .with_console(true)
+        .with_format("text".to_string())
+        .with_file(
+            lazylog::FileLogConfig::new(dir.path().join("app.log")).with_rotation_trigger(
+                lazylog::RotationTrigger::Time {
+                    period: lazylog::RotationPeriod::Daily,
+                },
+            ),
+        );
 
     let filter = tracing_subscriber::EnvFilter::try_new(cfg.level.clone()).unwrap();
 